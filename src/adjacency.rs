@@ -0,0 +1,199 @@
+use robotics_lib::interface::{robot_view, Direction};
+use robotics_lib::runner::Runnable;
+use robotics_lib::world::tile::Tile;
+use robotics_lib::world::World;
+
+/// A tile next to the robot
+#[derive(Debug, Clone)]
+pub struct Adjacent {
+    /// Where to `put`, `destroy` or `go` to reach the tile
+    pub direction: Direction,
+    /// The coordinate of the tile
+    pub coordinate: (usize, usize),
+    pub tile: Tile,
+}
+
+/// The four neighbours in a 3x3 view centred on the robot, in the order they are looked at
+const NEIGHBOURS: [(usize, usize); 4] = [(0, 1), (1, 0), (1, 2), (2, 1)];
+
+fn direction_of(i: usize, j: usize) -> Direction {
+    match (i, j) {
+        (0, 1) => Direction::Up,
+        (1, 0) => Direction::Left,
+        (1, 2) => Direction::Right,
+        _ => Direction::Down,
+    }
+}
+
+/// The coordinate one step from `from` in `direction`, `None` past the top or left edge
+pub fn towards(from: (usize, usize), direction: &Direction) -> Option<(usize, usize)> {
+    let (row, col) = from;
+    match direction {
+        Direction::Up => row.checked_sub(1).map(|row| (row, col)),
+        Direction::Down => Some((row + 1, col)),
+        Direction::Left => col.checked_sub(1).map(|col| (row, col)),
+        Direction::Right => Some((row, col + 1)),
+    }
+}
+
+/// The four neighbours of the robot in its view, `None` for the tiles that can't be seen
+///
+/// # Notes
+/// - `view` is what `robot_view` returns
+/// - the neighbours come Up, Left, Right, Down
+pub fn neighbours_in(view: &[Vec<Option<Tile>>]) -> Vec<(Direction, Option<&Tile>)> {
+    NEIGHBOURS.iter()
+        .map(|(i, j)| (direction_of(*i, *j), view.get(*i).and_then(|row| row.get(*j)).and_then(|tile| tile.as_ref())))
+        .collect()
+}
+
+/// The neighbour of the robot in `direction`, `None` if it can't be seen
+pub fn tile_towards<'a>(view: &'a [Vec<Option<Tile>>], direction: &Direction) -> Option<&'a Tile> {
+    let (i, j) = match direction {
+        Direction::Up => (0, 1),
+        Direction::Left => (1, 0),
+        Direction::Right => (1, 2),
+        Direction::Down => (2, 1),
+    };
+    view.get(i).and_then(|row| row.get(j)).and_then(|tile| tile.as_ref())
+}
+
+/// The neighbours of a view centred on `position` whose tile matches a predicate
+pub fn find_in(
+    view: &[Vec<Option<Tile>>],
+    position: (usize, usize),
+    matches: impl Fn(&Tile) -> bool
+) -> Vec<Adjacent> {
+    neighbours_in(view).into_iter()
+        .filter_map(|(direction, tile)| {
+            let tile = tile.filter(|tile| matches(tile))?;
+            let coordinate = towards(position, &direction)?;
+            Some(Adjacent { direction, coordinate, tile: tile.clone() })
+        })
+        .collect()
+}
+
+/// The neighbour of a view centred on `position` that matches a predicate and ranks highest
+///
+/// # Notes
+/// - on a tie the first neighbour, in the order of `neighbours_in`, wins
+pub fn best_in(
+    view: &[Vec<Option<Tile>>],
+    position: (usize, usize),
+    matches: impl Fn(&Tile) -> bool,
+    rank: impl Fn(&Tile) -> usize
+) -> Option<Adjacent> {
+    let mut best: Option<(usize, Adjacent)> = None;
+    for adjacent in find_in(view, position, matches) {
+        let score = rank(&adjacent.tile);
+        match &best {
+            Some((highest, _)) if score <= *highest => (),
+            _ => best = Some((score, adjacent))
+        }
+    }
+    best.map(|(_, adjacent)| adjacent)
+}
+
+/// Find the tiles next to the robot matching a predicate
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::adjacency::find_adjacent;
+/// ```
+///
+/// # Arguments
+/// - `robot`: The robot
+/// - `world`: The world in which the robot is
+/// - `matches`: Whether a tile is one of those looked for
+///
+/// # Returns
+/// - `Vec<Adjacent>`: The matching tiles, with their direction and coordinate
+pub fn find_adjacent(robot: &impl Runnable, world: &World, matches: impl Fn(&Tile) -> bool) -> Vec<Adjacent> {
+    let position = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+    find_in(&robot_view(robot, world), position, matches)
+}
+
+/// Find the tile next to the robot that matches a predicate and ranks highest
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::adjacency::best_adjacent;
+/// ```
+///
+/// # Arguments
+/// - `robot`: The robot
+/// - `world`: The world in which the robot is
+/// - `matches`: Whether a tile is one of those looked for
+/// - `rank`: The policy choosing among the matching tiles, the highest wins
+///
+/// # Returns
+/// - `Some(Adjacent)`: The chosen tile, with its direction and coordinate
+/// - `None`: No tile next to the robot matches
+pub fn best_adjacent(
+    robot: &impl Runnable,
+    world: &World,
+    matches: impl Fn(&Tile) -> bool,
+    rank: impl Fn(&Tile) -> usize
+) -> Option<Adjacent> {
+    let position = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+    best_in(&robot_view(robot, world), position, matches, rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::tile::{Content, TileType};
+
+    use super::*;
+
+    fn view(contents: [[Option<Content>; 3]; 3]) -> Vec<Vec<Option<Tile>>> {
+        contents.into_iter()
+            .map(|row| row.into_iter()
+                .map(|content| content.map(|content| Tile { tile_type: TileType::Grass, content, elevation: 0 }))
+                .collect())
+            .collect()
+    }
+
+    fn interactions(tile: &Tile) -> usize {
+        match tile.content {
+            Content::Market(n) => n,
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn find_matching_neighbours() {
+        let view = view([
+            [None, Some(Content::Market(2)), Some(Content::Market(9))],
+            [Some(Content::Tree(1)), Some(Content::None), None],
+            [None, Some(Content::Market(0)), None],
+        ]);
+
+        // The corner is not a neighbour, the tile on the right can't be seen
+        let markets = find_in(&view, (4, 4), |tile| matches!(tile.content, Content::Market(_)));
+        assert_eq!(markets.len(), 2);
+        assert_eq!(markets[0].coordinate, (3, 4));
+        assert_eq!(markets[1].coordinate, (5, 4));
+
+        assert_eq!(neighbours_in(&view).iter().filter(|(_, tile)| tile.is_none()).count(), 1);
+    }
+
+    #[test]
+    fn rank_matching_neighbours() {
+        let view = view([
+            [None, Some(Content::Market(2)), None],
+            [Some(Content::Market(5)), Some(Content::None), Some(Content::Market(5))],
+            [None, Some(Content::Market(1)), None],
+        ]);
+
+        // The first of the tied Markets wins
+        let best = best_in(&view, (1, 1), |tile| matches!(tile.content, Content::Market(_)), interactions).unwrap();
+        assert_eq!(best.coordinate, (1, 0));
+        assert_eq!(interactions(&best.tile), 5);
+
+        // Neighbours past the edge of the world are left out
+        let best = best_in(&view, (1, 0), |tile| matches!(tile.content, Content::Market(_)), interactions).unwrap();
+        assert_eq!(best.coordinate, (1, 1));
+
+        assert!(best_in(&view, (1, 1), |tile| matches!(tile.content, Content::Bank(_)), interactions).is_none());
+    }
+}
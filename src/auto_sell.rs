@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use robotics_lib::energy::Energy;
+use robotics_lib::event::events::Event;
+use robotics_lib::runner::backpack::BackPack;
+use robotics_lib::runner::Runnable;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::coordinates::Coordinate;
+use robotics_lib::world::tile::Content;
+use robotics_lib::world::World;
+use log::debug;
+
+use crate::adjacency;
+use crate::triggers::TriggerEngine;
+use crate::SwiftSeller;
+
+/// A condition that makes `AutoSell` sell after the robot's tick
+#[derive(Debug, Clone, PartialEq)]
+pub enum SellTrigger {
+    /// The robot stands next to a Market with interactions left
+    AdjacentToMarket,
+    /// The backpack is filled at least to the given percentage
+    BackpackFill(usize),
+    /// The backpack holds at least the given quantity of an item
+    ItemCount(Content, usize),
+}
+
+/// Wraps any robot so that it sells automatically at the end of its ticks
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::auto_sell::{AutoSell, SellTrigger};
+/// ```
+///
+/// # Notes
+/// - `process_tick` and `handle_event` are passed through to the wrapped robot
+/// - the sale happens after the wrapped robot's tick, as soon as any trigger fires
+/// - a `TriggerEngine` can be attached to fire sales from the events the robot receives, it keeps
+///   firing until a sale actually sells something
+pub struct AutoSell<R: Runnable> {
+    inner: R,
+    items: Vec<Content>,
+    triggers: Vec<SellTrigger>,
+    engine: Option<TriggerEngine>,
+    last_sale: Option<Result<HashMap<Content, usize>, LibError>>,
+}
+
+impl<R: Runnable> AutoSell<R> {
+    /// Wraps `inner`, selling `items` (in that order) whenever a trigger fires
+    pub fn new(inner: R, items: Vec<Content>) -> Self {
+        AutoSell {
+            inner,
+            items,
+            triggers: Vec::new(),
+            engine: None,
+            last_sale: None,
+        }
+    }
+
+    pub fn with_trigger(mut self, trigger: SellTrigger) -> Self {
+        self.triggers.push(trigger);
+        self
+    }
+
+    /// Sells whenever the engine, fed with every event the robot receives, says so
+    pub fn with_engine(mut self, engine: TriggerEngine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// The outcome of the latest automatic sale, `None` if no trigger fired yet
+    pub fn last_sale(&self) -> Option<&Result<HashMap<Content, usize>, LibError>> {
+        self.last_sale.as_ref()
+    }
+
+    fn fired(&self, trigger: &SellTrigger, world: &World) -> bool {
+        let backpack = self.inner.get_backpack();
+        match trigger {
+            SellTrigger::AdjacentToMarket => {
+                !adjacency::find_adjacent(&self.inner, world, |tile| matches!(tile.content, Content::Market(n) if n > 0)).is_empty()
+            }
+            SellTrigger::BackpackFill(percentage) => {
+                let held: usize = backpack.get_contents().values().sum();
+                backpack.get_size() > 0 && held * 100 >= percentage * backpack.get_size()
+            }
+            SellTrigger::ItemCount(item, qty) => {
+                backpack.get_contents().get(item).is_some_and(|held| held >= qty)
+            }
+        }
+    }
+}
+
+impl<R: Runnable> Runnable for AutoSell<R> {
+    fn process_tick(&mut self, world: &mut World) {
+        self.inner.process_tick(world);
+
+        let engine_fired = self.engine.as_ref().is_some_and(|engine| engine.should_sell());
+        if engine_fired || self.triggers.iter().any(|trigger| self.fired(trigger, world)) {
+            debug!("auto sell fired: engine={} items={:?}", engine_fired, self.items);
+            let sale = SwiftSeller::swift_seller(&mut self.inner, world, self.items.clone());
+            // Only a sale that sold something starts the engine's counts again
+            let sold_anything = sale.as_ref().is_ok_and(|sold| sold.values().any(|qty| *qty > 0));
+            if let (Some(engine), true) = (&mut self.engine, sold_anything) {
+                engine.sale_done();
+            }
+            self.last_sale = Some(sale);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        if let Some(engine) = &mut self.engine {
+            engine.observe(&event);
+        }
+        self.inner.handle_event(event)
+    }
+
+    fn get_energy(&self) -> &Energy {
+        self.inner.get_energy()
+    }
+    fn get_energy_mut(&mut self) -> &mut Energy {
+        self.inner.get_energy_mut()
+    }
+    fn get_coordinate(&self) -> &Coordinate {
+        self.inner.get_coordinate()
+    }
+    fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+        self.inner.get_coordinate_mut()
+    }
+    fn get_backpack(&self) -> &BackPack {
+        self.inner.get_backpack()
+    }
+    fn get_backpack_mut(&mut self) -> &mut BackPack {
+        self.inner.get_backpack_mut()
+    }
+}
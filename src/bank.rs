@@ -0,0 +1,57 @@
+use robotics_lib::interface::{put, Direction};
+use robotics_lib::runner::Runnable;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::World;
+use log::debug;
+
+use crate::adjacency;
+
+/// Coins held in the backpack
+pub fn coins_held(robot: &impl Runnable) -> usize {
+    robot.get_backpack().get_contents().get(&Content::Coin(0)).copied().unwrap_or(0)
+}
+
+/// Find the adjacent Bank with the most room left
+///
+/// # Returns
+/// - `Some((direction, coordinate))`: Where the Bank is
+/// - `None`: There is no Bank with room next to the robot
+pub fn adjacent_bank(robot: &impl Runnable, world: &World) -> Option<(Direction, (usize, usize))> {
+    let room = |tile: &Tile| match &tile.content {
+        Content::Bank(range) => range.end.saturating_sub(range.start),
+        _ => 0
+    };
+    adjacency::best_adjacent(robot, world, |tile| room(tile) > 0, room)
+        .map(|bank| (bank.direction, bank.coordinate))
+}
+
+/// Deposit the coins held in the backpack into an adjacent Bank
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::bank::deposit_coins;
+/// ```
+///
+/// # Arguments
+/// - `robot`: The robot
+/// - `world`: The world in which the robot is
+///
+/// # Returns
+/// - `((usize, usize), usize)`: The coordinate of the Bank and the coins deposited
+/// - `LibError`: The error that occurred
+///
+/// # Errors
+/// - `OperationNotAllowed`: There is no Bank with room next to the robot, or no coin to deposit
+/// - any error `put` returns
+pub fn deposit_coins(robot: &mut impl Runnable, world: &mut World) -> Result<((usize, usize), usize), LibError> {
+    let (direction, bank) = adjacent_bank(robot, world).ok_or(LibError::OperationNotAllowed)?;
+    let coins = coins_held(robot);
+    if coins == 0 {
+        return Err(LibError::OperationNotAllowed);
+    }
+
+    let deposited = put(robot, world, Content::Coin(0), coins, direction)?;
+    debug!("coins deposited: bank={:?} coins={} held={}", bank, deposited, coins);
+    Ok((bank, deposited))
+}
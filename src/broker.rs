@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use log::debug;
+
+/// Identifies a robot sharing a `MarketBroker`, handed out by `MarketBroker::register`
+pub type RobotId = usize;
+
+/// How long the interactions reserved before walking to a Market hold, see
+/// `SwiftSeller::walk_and_sell` and `SalePlan::with_broker`
+pub const TRIP_TTL: Duration = Duration::from_secs(60);
+
+/// Interactions a robot holds at a Market
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reservation {
+    pub robot: RobotId,
+    pub market: (usize, usize),
+    pub interactions: usize,
+    /// When the reservation stops holding, unless renewed
+    pub expires: Instant,
+}
+
+#[derive(Debug, Default)]
+struct BrokerState {
+    robots: usize,
+    markets: HashMap<(usize, usize), usize>,
+    reservations: Vec<Reservation>,
+}
+
+impl BrokerState {
+    fn expire(&mut self, now: Instant) -> usize {
+        let before = self.reservations.len();
+        self.reservations.retain(|reservation| reservation.expires > now);
+        before - self.reservations.len()
+    }
+
+    fn reserved(&self, market: (usize, usize), except: Option<RobotId>) -> usize {
+        self.reservations.iter()
+            .filter(|reservation| reservation.market == market && Some(reservation.robot) != except)
+            .map(|reservation| reservation.interactions)
+            .sum()
+    }
+}
+
+/// Shares Market interactions between several robots in the same process
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::broker::MarketBroker;
+/// let broker = MarketBroker::new();
+/// let robot = broker.register();
+/// ```
+///
+/// # Notes
+/// - clones share the same state, hand one to every robot
+/// - reserve interactions before walking to a Market, so that two robots don't both count on
+///   its last interaction
+/// - a robot never holds more than its fair share of a Market: its interactions divided by the
+///   robots registered, rounded up
+/// - reservations expire, so that a robot that never shows up doesn't hold a Market forever
+#[derive(Debug, Clone, Default)]
+pub struct MarketBroker {
+    state: Arc<Mutex<BrokerState>>,
+}
+
+impl MarketBroker {
+    pub fn new() -> Self {
+        MarketBroker::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, BrokerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers a robot, every robot sharing the broker must call it once
+    pub fn register(&self) -> RobotId {
+        let mut state = self.state();
+        state.robots += 1;
+        state.robots - 1
+    }
+
+    /// Records the interactions a Market has left, as seen by any robot
+    pub fn update(&self, market: (usize, usize), interactions: usize) {
+        self.state().markets.insert(market, interactions);
+    }
+
+    /// The interactions of a Market nobody holds, `None` if the Market has never been recorded
+    pub fn available(&self, market: (usize, usize)) -> Option<usize> {
+        let mut state = self.state();
+        state.expire(Instant::now());
+        let interactions = *state.markets.get(&market)?;
+        Some(interactions.saturating_sub(state.reserved(market, None)))
+    }
+
+    /// The interactions `robot` holds at a Market
+    pub fn reserved_by(&self, robot: RobotId, market: (usize, usize)) -> usize {
+        let mut state = self.state();
+        state.expire(Instant::now());
+        state.reservations.iter()
+            .find(|reservation| reservation.robot == robot && reservation.market == market)
+            .map_or(0, |reservation| reservation.interactions)
+    }
+
+    /// Reserve interactions at a Market for `robot`
+    ///
+    /// # Arguments
+    /// - `robot`: The robot reserving
+    /// - `market`: The coordinate of the Market
+    /// - `wanted`: The interactions the robot would like to hold, in total
+    /// - `ttl`: How long the reservation holds
+    ///
+    /// # Returns
+    /// - `usize`: The interactions the robot now holds, possibly fewer than wanted, 0 if none
+    ///
+    /// # Notes
+    /// - reserving again replaces the robot's reservation at that Market and renews it
+    pub fn reserve(&self, robot: RobotId, market: (usize, usize), wanted: usize, ttl: Duration) -> usize {
+        self.reserve_at(robot, market, wanted, ttl, Instant::now())
+    }
+
+    /// `reserve`, as if it were `now`
+    pub fn reserve_at(&self, robot: RobotId, market: (usize, usize), wanted: usize, ttl: Duration, now: Instant) -> usize {
+        let mut state = self.state();
+        state.expire(now);
+
+        let interactions = state.markets.get(&market).copied().unwrap_or(0);
+        let free = interactions.saturating_sub(state.reserved(market, Some(robot)));
+        let fair_share = interactions.div_ceil(state.robots.max(1));
+        let granted = wanted.min(free).min(fair_share);
+
+        state.reservations.retain(|reservation| !(reservation.robot == robot && reservation.market == market));
+        if granted > 0 {
+            state.reservations.push(Reservation { robot, market, interactions: granted, expires: now + ttl });
+        }
+        debug!("interactions reserved: robot={} market={:?} wanted={} granted={}", robot, market, wanted, granted);
+        granted
+    }
+
+    /// Reserve `wanted` interactions at a Market before walking to it, renewing what `robot` holds
+    /// there already; a Market nobody recorded yet is recorded with the interactions `known` returns
+    pub(crate) fn reserve_for_trip(
+        &self,
+        robot: RobotId,
+        market: (usize, usize),
+        wanted: usize,
+        known: impl FnOnce() -> usize
+    ) -> usize {
+        if self.available(market).is_none() {
+            self.update(market, known());
+        }
+        let held = self.reserved_by(robot, market);
+        self.reserve(robot, market, wanted.max(held), TRIP_TTL)
+    }
+
+    /// Records that `robot` used interactions at a Market, taking them off both its reservation
+    /// and the Market
+    pub fn consume(&self, robot: RobotId, market: (usize, usize), used: usize) {
+        let mut state = self.state();
+        if let Some(interactions) = state.markets.get_mut(&market) {
+            *interactions = interactions.saturating_sub(used);
+        }
+        if let Some(reservation) = state.reservations.iter_mut()
+            .find(|reservation| reservation.robot == robot && reservation.market == market) {
+            reservation.interactions = reservation.interactions.saturating_sub(used);
+        }
+        state.reservations.retain(|reservation| reservation.interactions > 0);
+    }
+
+    /// Gives back what `robot` holds at a Market
+    pub fn release(&self, robot: RobotId, market: (usize, usize)) {
+        self.state().reservations.retain(|reservation| !(reservation.robot == robot && reservation.market == market));
+    }
+
+    /// Drops the reservations that expired, returns how many
+    pub fn expire(&self) -> usize {
+        self.expire_at(Instant::now())
+    }
+
+    /// `expire`, as if it were `now`
+    pub fn expire_at(&self, now: Instant) -> usize {
+        self.state().expire(now)
+    }
+
+    /// The reservations currently holding
+    pub fn reservations(&self) -> Vec<Reservation> {
+        let mut state = self.state();
+        state.expire(Instant::now());
+        state.reservations.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn last_interaction_goes_to_one_robot() {
+        let broker = MarketBroker::new();
+        let first = broker.register();
+        let second = broker.register();
+        broker.update((1, 1), 1);
+
+        assert_eq!(broker.reserve(first, (1, 1), 1, TTL), 1);
+        assert_eq!(broker.reserve(second, (1, 1), 1, TTL), 0);
+
+        // Once released, the other robot can have it
+        broker.release(first, (1, 1));
+        assert_eq!(broker.reserve(second, (1, 1), 1, TTL), 1);
+        assert_eq!(broker.available((1, 1)), Some(0));
+    }
+
+    #[test]
+    fn share_fairly_and_expire() {
+        let broker = MarketBroker::new();
+        let robots: Vec<RobotId> = (0..3).map(|_| broker.register()).collect();
+        broker.update((0, 3), 7);
+
+        // 7 interactions over 3 robots: no more than 3 each
+        let now = Instant::now();
+        assert_eq!(broker.reserve_at(robots[0], (0, 3), 10, TTL, now), 3);
+        assert_eq!(broker.reserve_at(robots[1], (0, 3), 10, Duration::from_secs(1), now), 3);
+        assert_eq!(broker.reserve_at(robots[2], (0, 3), 10, TTL, now), 1);
+
+        // Two seconds later the second robot's reservation expired
+        assert_eq!(broker.expire_at(now + Duration::from_secs(2)), 1);
+        assert_eq!(broker.available((0, 3)), Some(3));
+
+        broker.consume(robots[0], (0, 3), 2);
+        assert_eq!(broker.reserved_by(robots[0], (0, 3)), 1);
+        assert_eq!(broker.available((0, 3)), Some(3));
+    }
+
+    #[test]
+    fn share_between_threads() {
+        let broker = MarketBroker::new();
+        broker.update((2, 2), 4);
+
+        let robots: Vec<RobotId> = (0..4).map(|_| broker.register()).collect();
+        let handles: Vec<_> = robots.into_iter()
+            .map(|robot| {
+                let broker = broker.clone();
+                thread::spawn(move || broker.reserve(robot, (2, 2), 4, TTL))
+            })
+            .collect();
+        let granted: usize = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+        assert_eq!(granted, 4);
+    }
+    #[test]
+    fn reserve_before_the_trip() {
+        let broker = MarketBroker::new();
+        let first = broker.register();
+        let second = broker.register();
+
+        // Nobody recorded the Market yet, the robot's map tells how many interactions it has
+        assert_eq!(broker.reserve_for_trip(first, (0, 1), 1, || 3), 1);
+        assert_eq!(broker.available((0, 1)), Some(2));
+
+        // What is held already is kept, the map is not looked at again
+        assert_eq!(broker.reserve_for_trip(first, (0, 1), 0, || 0), 1);
+        assert_eq!(broker.reserve_for_trip(second, (0, 1), 5, || 0), 2);
+        assert_eq!(broker.available((0, 1)), Some(0));
+    }
+}
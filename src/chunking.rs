@@ -0,0 +1,79 @@
+use robotics_lib::runner::backpack::BackPack;
+use robotics_lib::world::tile::Content;
+
+use crate::goal::free_slots;
+use crate::prices::PriceBook;
+
+/// Sells an item in several `put`s, each small enough for its coins to fit in the backpack
+///
+/// # Notes
+/// - every chunk takes one interaction, as long as the Market has enough to spare for the chunks an
+///   item needs, they are all offered
+/// - a chunk that takes an interaction a later item may need is only offered when it is expected to
+///   earn at least `min_coins_per_interaction`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunking {
+    pub min_coins_per_interaction: usize,
+}
+
+impl Default for Chunking {
+    fn default() -> Self {
+        Chunking {
+            min_coins_per_interaction: 1,
+        }
+    }
+}
+
+impl Chunking {
+    /// How many of the `left` units of `item` to offer with the next `put`, 0 to stop
+    ///
+    /// # Arguments
+    /// - `spare`: The interactions the item may use without taking one from the items after it
+    pub(crate) fn chunk(&self, item: &Content, left: usize, spare: usize, backpack: &BackPack, prices: &PriceBook) -> usize {
+        self.chunk_within(item, left, spare, free_slots(backpack), prices)
+    }
+
+    fn chunk_within(&self, item: &Content, left: usize, spare: usize, free: usize, prices: &PriceBook) -> usize {
+        let price = prices.price(item);
+        if price <= 0.0 {
+            return left;
+        }
+
+        // Keep the coins within the free slots
+        let fits = (free as f64 / price).floor() as usize;
+        if fits >= left {
+            return left;
+        }
+        if fits == 0 {
+            return 0;
+        }
+        // Weigh the interaction against the coins, unless the Market has enough for every chunk
+        if left.div_ceil(fits) > spare && prices.expected_coins(item, fits) < self.min_coins_per_interaction.max(1) {
+            return 0;
+        }
+        fits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weigh_interactions_against_coins() {
+        let chunking = Chunking { min_coins_per_interaction: 10 };
+        let mut prices = PriceBook::new();
+        prices.set(&Content::Rock(0), 2.0);
+
+        // Everything fits, or nothing does
+        assert_eq!(chunking.chunk_within(&Content::Rock(0), 3, 1, 6, &prices), 3);
+        assert_eq!(chunking.chunk_within(&Content::Rock(0), 3, 5, 1, &prices), 0);
+
+        // 2 rocks at a time, 3 chunks for 6 rocks: offered if the Market has 3 interactions to spare
+        assert_eq!(chunking.chunk_within(&Content::Rock(0), 6, 3, 4, &prices), 2);
+        assert_eq!(chunking.chunk_within(&Content::Rock(0), 6, 2, 4, &prices), 0);
+
+        // Worth the interaction anyway
+        assert_eq!(Chunking::default().chunk_within(&Content::Rock(0), 6, 0, 4, &prices), 2);
+    }
+}
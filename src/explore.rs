@@ -0,0 +1,113 @@
+use robotics_lib::interface::{discover_tiles, robot_map};
+use robotics_lib::runner::Runnable;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::Content;
+use robotics_lib::world::World;
+use log::{debug, info};
+
+use crate::registry::MarketRegistry;
+
+/// Energy assumed for discovering one tile, until the first batch tells the real cost
+const DISCOVERY_ENERGY_ESTIMATE: usize = 3;
+
+/// How many tiles are discovered with a single call, so that the search can stop early
+const DISCOVERY_BATCH: usize = 8;
+
+/// What a market discovery found
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Discovery {
+    /// The Markets found during the search and their interactions left, as `(coordinate, interactions)`
+    pub markets: Vec<((usize, usize), usize)>,
+    /// The energy spent discovering tiles
+    pub energy_spent: usize,
+}
+
+/// Search outward for a Market with interactions left, within an energy budget
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::explore::explore_for_market;
+/// ```
+///
+/// # Arguments
+/// - `robot`: The robot
+/// - `world`: The world in which the robot is
+/// - `registry`: The Markets already known, the ones found are recorded here
+/// - `energy_budget`: The energy the search may spend
+///
+/// # Returns
+/// - `Discovery`: The Markets found and the energy spent
+/// - `LibError`: The error that occurred
+///
+/// # Errors
+/// - `OperationNotAllowed`: The robot's map cannot be read
+/// - any error `discover_tiles` returns before a single tile could be discovered
+///
+/// # Notes
+/// - nothing is discovered if the registry or the robot's map already know of a usable Market
+/// - unknown tiles are discovered ring by ring around the robot, the closest ones first
+/// - the search stops at the first batch containing a Market with interactions left
+pub fn explore_for_market(
+    robot: &mut impl Runnable,
+    world: &mut World,
+    registry: &mut MarketRegistry,
+    energy_budget: usize
+) -> Result<Discovery, LibError> {
+
+    let mut discovery = Discovery::default();
+
+    registry.refresh(world);
+    if !registry.usable().is_empty() {
+        return Ok(discovery);
+    }
+
+    let map = robot_map(world).ok_or(LibError::OperationNotAllowed)?;
+    let size = map.len();
+    let (row, col) = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+
+    let mut discovered: usize = 0;
+    for radius in 1..size {
+        // The unknown tiles on the ring at `radius`, closest first
+        let mut ring: Vec<(usize, usize)> = (row.saturating_sub(radius)..(row + radius + 1).min(size))
+            .flat_map(|i| (col.saturating_sub(radius)..(col + radius + 1).min(size)).map(move |j| (i, j)))
+            .filter(|(i, j)| i.abs_diff(row).max(j.abs_diff(col)) == radius)
+            .filter(|(i, j)| matches!(map.get(*i).and_then(|r| r.get(*j)), Some(None)))
+            .collect();
+        ring.sort_by_key(|(i, j)| i.abs_diff(row) + j.abs_diff(col));
+
+        for batch in ring.chunks(DISCOVERY_BATCH) {
+            // Learn the cost of a tile from what has been spent so far
+            let tile_energy = if discovered > 0 {
+                discovery.energy_spent.div_ceil(discovered).max(1)
+            } else {
+                DISCOVERY_ENERGY_ESTIMATE
+            };
+            let affordable = (energy_budget.saturating_sub(discovery.energy_spent) / tile_energy).min(batch.len());
+            if affordable == 0 {
+                return Ok(discovery);
+            }
+
+            let before = robot.get_energy().get_energy_level();
+            let tiles = match discover_tiles(robot, world, &batch[..affordable]) {
+                Ok(tiles) => tiles,
+                Err(e) if discovered == 0 => return Err(e),
+                Err(_) => return Ok(discovery)
+            };
+            discovery.energy_spent += before.saturating_sub(robot.get_energy().get_energy_level());
+            discovered += affordable;
+            debug!("discovered tiles: radius={} tiles={} energy_spent={}", radius, affordable, discovery.energy_spent);
+
+            for (coordinate, tile) in tiles {
+                if let Some(Content::Market(n)) = tile.map(|tile| tile.content) {
+                    info!("market discovered: coordinate={:?} interactions={}", coordinate, n);
+                    registry.record(coordinate, n);
+                    discovery.markets.push((coordinate, n));
+                }
+            }
+            if discovery.markets.iter().any(|(_, n)| *n > 0) {
+                return Ok(discovery);
+            }
+        }
+    }
+    Ok(discovery)
+}
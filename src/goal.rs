@@ -0,0 +1,120 @@
+use robotics_lib::runner::backpack::BackPack;
+use robotics_lib::world::tile::Content;
+
+use crate::prices::PriceBook;
+use crate::report::SaleReport;
+
+/// What a sale is meant to achieve
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SaleGoal {
+    /// Sell everything in the list, the default
+    #[default]
+    Everything,
+    /// Sell only what is needed to earn at least the given coins
+    Coins(usize),
+    /// Sell only what is needed to leave at least the given free slots in the backpack, once the
+    /// earned coins are in it too
+    ///
+    /// Every coin takes a slot, so only items worth less than a coin free any: with the default
+    /// `PriceBook`, where every item is worth a coin or more, nothing is sold and every item is
+    /// skipped with `SkipReason::CannotFreeSpace`; to make room, bank the coins with
+    /// `SwiftSeller::sell_and_bank`
+    FreeSlots(usize),
+}
+
+/// Free slots left in the backpack
+pub(crate) fn free_slots(backpack: &BackPack) -> usize {
+    let held: usize = backpack.get_contents().values().sum();
+    backpack.get_size().saturating_sub(held)
+}
+
+/// Slots freed by selling a unit of `item`: the unit's own, minus the ones its coins take back
+fn freed_per_unit(item: &Content, prices: &PriceBook) -> f64 {
+    1.0 - prices.price(item)
+}
+
+/// Whether selling `item` frees any slot at all
+pub(crate) fn frees_slots(item: &Content, prices: &PriceBook) -> bool {
+    freed_per_unit(item, prices) > 0.0
+}
+
+impl SaleGoal {
+    /// Whether the sale can stop, given what it achieved so far
+    pub(crate) fn is_reached(&self, report: &SaleReport, backpack: &BackPack) -> bool {
+        match self {
+            SaleGoal::Everything => false,
+            SaleGoal::Coins(coins) => report.coins_earned >= *coins,
+            SaleGoal::FreeSlots(slots) => free_slots(backpack) >= *slots,
+        }
+    }
+
+    /// The order in which the items of `vec` should be sold
+    ///
+    /// # Notes
+    /// - to make room, the least valuable items go first and, among them, the bulkiest
+    /// - every other goal keeps the order chosen by the user
+    pub(crate) fn order(&self, mut vec: Vec<Content>, backpack: &BackPack, prices: &PriceBook) -> Vec<Content> {
+        if let SaleGoal::FreeSlots(_) = self {
+            let held = |item: &Content| backpack.get_contents().get(item).copied().unwrap_or(0);
+            vec.sort_by(|a, b| {
+                prices.price(a).total_cmp(&prices.price(b))
+                    .then_with(|| held(b).cmp(&held(a)))
+            });
+        }
+        vec
+    }
+
+    /// How many of the `held` units of `item` should be offered to get closer to the goal
+    ///
+    /// # Notes
+    /// - items the price book knows nothing about are offered in full
+    /// - to make room, items worth a coin or more are never offered: the coins would take the
+    ///   slots they free
+    pub(crate) fn quantity(
+        &self,
+        item: &Content,
+        held: usize,
+        report: &SaleReport,
+        backpack: &BackPack,
+        prices: &PriceBook
+    ) -> usize {
+        match self {
+            SaleGoal::Everything => held,
+            SaleGoal::Coins(coins) => {
+                let missing = coins.saturating_sub(report.coins_earned);
+                let price = prices.price(item);
+                if price <= 0.0 {
+                    held
+                } else {
+                    held.min((missing as f64 / price).ceil() as usize)
+                }
+            }
+            SaleGoal::FreeSlots(slots) => {
+                let missing = slots.saturating_sub(free_slots(backpack));
+                let freed = freed_per_unit(item, prices);
+                if freed <= 0.0 {
+                    0
+                } else {
+                    held.min((missing as f64 / freed).ceil() as usize)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_prices_free_no_slots() {
+        let prices = PriceBook::default();
+        for item in [Content::Rock(0), Content::Tree(0), Content::Fish(0)] {
+            assert!(!frees_slots(&item, &prices), "{:?} frees slots", item);
+        }
+
+        let mut prices = PriceBook::default();
+        prices.set(&Content::Rock(0), 0.5);
+        assert_eq!(freed_per_unit(&Content::Rock(0), &prices), 0.5);
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use robotics_lib::interface::{destroy, go, look_at_sky, robot_map};
+use robotics_lib::runner::Runnable;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::World;
+use log::{debug, info};
+
+use crate::adjacency;
+use crate::navigation;
+use crate::planner;
+use crate::report::SaleReport;
+use crate::{SwiftSeller, PUT_ENERGY_ESTIMATE};
+
+/// Energy assumed for a single `destroy`, until the first one tells the real cost
+const DESTROY_ENERGY_ESTIMATE: usize = 5;
+
+/// What a harvest-and-sell loop achieved
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HarvestReport {
+    /// The items collected with `destroy` and their quantity
+    pub harvested: HashMap<Content, usize>,
+    /// Every sale made, in order
+    pub sales: Vec<SaleReport>,
+    /// The items sold and their quantity, over all sales
+    pub items_sold: HashMap<Content, usize>,
+    /// The coins earned, over all sales
+    pub coins_earned: usize,
+    /// The energy spent walking, harvesting and selling
+    pub energy_spent: usize,
+}
+
+/// The items a harvest collects and sells
+fn resources() -> Vec<Content> {
+    vec![Content::Tree(0), Content::Rock(0), Content::Fish(0)]
+}
+
+fn amount(tile: &Tile) -> usize {
+    match tile.content {
+        Content::Tree(n) | Content::Rock(n) | Content::Fish(n) => n,
+        _ => 0
+    }
+}
+
+fn held(robot: &impl Runnable) -> HashMap<Content, usize> {
+    robot.get_backpack().get_contents().iter()
+        .filter(|(item, _)| resources().contains(item))
+        .map(|(item, qty)| (item.clone(), *qty))
+        .collect()
+}
+
+fn energy_spent(robot: &impl Runnable, start: usize) -> usize {
+    start.saturating_sub(robot.get_energy().get_energy_level())
+}
+
+/// Walk next to the Market and sell what has been harvested to it, `false` if nothing could be sold
+fn sell_batch(
+    seller: &mut SwiftSeller,
+    robot: &mut impl Runnable,
+    world: &mut World,
+    market: (usize, usize),
+    report: &mut HarvestReport
+) -> bool {
+    if held(robot).values().all(|qty| *qty == 0) {
+        return false;
+    }
+
+    match seller.walk_and_sell(robot, world, market, resources()) {
+        Ok(sale) => {
+            for (item, qty) in &sale.items_sold {
+                *report.items_sold.entry(item.clone()).or_insert(0) += qty;
+            }
+            report.coins_earned += sale.coins_earned;
+            let sold_anything = !sale.items_sold.is_empty();
+            report.sales.push(sale);
+            sold_anything
+        },
+        Err(e) => {
+            debug!("batch not sold: market={:?} error={:?}", market, e);
+            false
+        }
+    }
+}
+
+/// Harvest the Trees, Rocks and Fish around a known Market and sell them there, within an energy budget
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::harvest::harvest_and_sell;
+/// ```
+///
+/// # Arguments
+/// - `seller`: The seller making the sales, with its goal, observers and prices
+/// - `robot`: The robot
+/// - `world`: The world in which the robot is
+/// - `market`: The coordinate of the Market to sell to
+/// - `reach`: How far from the Market, in steps, resources are harvested
+/// - `energy_budget`: The energy the whole loop may spend
+///
+/// # Returns
+/// - `HarvestReport`: What was harvested and sold, and the energy spent
+/// - `LibError`: The error that occurred
+///
+/// # Errors
+/// - `OperationNotAllowed`: The robot doesn't know of a Market with interactions left at `market`,
+///   or the seller's `SaleOptions::markets` doesn't allow it
+///
+/// # Notes
+/// - only the tiles known to the robot and within `reach` of the Market are harvested, the closest
+///   ones in energy first
+/// - the budget never goes past the robot's energy level when the loop starts
+/// - a harvest is only started if the energy left covers the walk back and the sale
+/// - the harvest is sold in batches, whenever the backpack fills up, and once more at the end, only
+///   to `market` and the way `SwiftSeller::walk_and_sell` does
+/// - the robot only walks while the seller's `SaleOptions::window` allows it, the loop stops otherwise
+pub fn harvest_and_sell(
+    seller: &mut SwiftSeller,
+    robot: &mut impl Runnable,
+    world: &mut World,
+    market: (usize, usize),
+    reach: usize,
+    energy_budget: usize
+) -> Result<HarvestReport, LibError> {
+
+    let map = robot_map(world).ok_or(LibError::OperationNotAllowed)?;
+    if !seller.options().markets.allows(market) || navigation::market_at(&map, market) < 1 {
+        return Err(LibError::OperationNotAllowed);
+    }
+
+    let start = robot.get_energy().get_energy_level();
+    let energy_budget = energy_budget.min(start);
+    let mut report = HarvestReport::default();
+    let mut destroy_energy = DESTROY_ENERGY_ESTIMATE;
+
+    loop {
+        let map = robot_map(world).ok_or(LibError::OperationNotAllowed)?;
+        let weather = look_at_sky(world).get_weather_condition();
+        let position = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        let energy_left = energy_budget.saturating_sub(energy_spent(robot, start));
+
+        // The known resources close enough to the Market
+        let targets: Vec<(usize, usize)> = map.iter().enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, tile)| ((i, j), tile)))
+            .filter(|(coordinate, tile)| {
+                coordinate.0.abs_diff(market.0) + coordinate.1.abs_diff(market.1) <= reach
+                    && tile.as_ref().is_some_and(|tile| amount(tile) > 0)
+            })
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        if targets.is_empty() {
+            debug!("nothing left to harvest: market={:?} reach={}", market, reach);
+            break;
+        }
+
+        let Ok(trip) = planner::plan_trip(
+            &map,
+            position,
+            |position| targets.iter().any(|target| navigation::is_adjacent(*target, position)),
+            &weather,
+            energy_left,
+            destroy_energy
+        ) else {
+            break;
+        };
+        // Keep enough energy to walk back and sell
+        let Ok(back) = planner::plan_trip(
+            &map,
+            trip.destination,
+            |position| navigation::is_adjacent(market, position),
+            &weather,
+            usize::MAX,
+            0
+        ) else {
+            break;
+        };
+        let needed = trip.energy + destroy_energy + back.energy + resources().len() * PUT_ENERGY_ESTIMATE;
+        if needed > energy_left {
+            debug!("harvest out of budget: needed={} left={}", needed, energy_left);
+            break;
+        }
+
+        let walked = trip.path.into_iter().all(|direction| {
+            seller.options().window.can_travel(&look_at_sky(world)) && go(robot, world, direction).is_ok()
+        });
+        if !walked {
+            debug!("harvest stopped on the way: weather={:?}", look_at_sky(world).get_weather_condition());
+            break;
+        }
+        // Only the targets: the resources next to the robot may be out of reach
+        let Some(target) = adjacency::find_adjacent(robot, world, |tile| amount(tile) > 0).into_iter()
+            .filter(|adjacent| targets.contains(&adjacent.coordinate))
+            .max_by_key(|adjacent| amount(&adjacent.tile)) else {
+            break;
+        };
+
+        let before = held(robot);
+        let energy_before = robot.get_energy().get_energy_level();
+        match destroy(robot, world, target.direction.clone()) {
+            Ok(_) => {
+                destroy_energy = energy_before.saturating_sub(robot.get_energy().get_energy_level()).max(1);
+                for (item, qty) in held(robot) {
+                    let gained = qty.saturating_sub(before.get(&item).copied().unwrap_or(0));
+                    if gained > 0 {
+                        debug!("harvested: item={:?} qty={} coordinate={:?}", item, gained, target.coordinate);
+                        *report.harvested.entry(item).or_insert(0) += gained;
+                    }
+                }
+            },
+            Err(LibError::NotEnoughSpace(_)) => {
+                // The backpack can't take the resource, make room
+                if !sell_batch(seller, robot, world, market, &mut report) {
+                    break;
+                }
+                continue;
+            },
+            Err(e) => {
+                debug!("cannot harvest: coordinate={:?} error={:?}", target.coordinate, e);
+                break;
+            }
+        }
+
+        // Sell as soon as the backpack is full
+        let used: usize = robot.get_backpack().get_contents().values().sum();
+        if used >= robot.get_backpack().get_size() && !sell_batch(seller, robot, world, market, &mut report) {
+            break;
+        }
+    }
+
+    sell_batch(seller, robot, world, market, &mut report);
+    report.energy_spent = energy_spent(robot, start);
+    info!("harvest complete: harvested={:?} sold={:?} coins={} energy={}", report.harvested, report.items_sold, report.coins_earned, report.energy_spent);
+    Ok(report)
+}
@@ -1,14 +1,469 @@
+pub mod adjacency;
+pub mod auto_sell;
+pub mod bank;
+pub mod broker;
+pub mod chunking;
+pub mod explore;
+pub mod goal;
+pub mod harvest;
+pub mod market_filter;
+pub mod navigation;
+pub mod observer;
+pub mod options;
+pub mod plan;
+pub mod planner;
+pub mod prices;
+pub mod quota;
+pub mod registry;
+pub mod report;
+pub mod route;
+pub mod stash;
+pub mod trace;
+pub mod triggers;
+pub mod verify;
+pub mod window;
+
 use std::collections::HashMap;
-use robotics_lib::interface::{Tools, robot_view, put, Direction};
+use std::fmt;
+use robotics_lib::interface::{Tools, robot_view, robot_map, look_at_sky, go, put, Direction};
 use robotics_lib::world::World;
 use robotics_lib::runner::Runnable;
 use robotics_lib::utils::LibError;
-use robotics_lib::world::tile::Content;
-
-pub struct SwiftSeller;
+use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::event::events::Event;
+use log::{debug, error, info, warn};
+
+use crate::broker::{MarketBroker, RobotId};
+use crate::chunking::Chunking;
+use crate::goal::SaleGoal;
+use crate::market_filter::MarketFilter;
+use crate::observer::{SaleObserver, SelectionReason, SkipReason, StopReason};
+use crate::options::SaleOptions;
+use crate::prices::PriceBook;
+use crate::quota::DailyQuota;
+use crate::registry::MarketRegistry;
+use crate::report::{BankReport, MarketSurprise, SaleReport};
+use crate::route::RoutePlan;
+use crate::stash::{ShortOfSpace, Stash};
+use crate::trace::{SaleTrace, TraceEntry};
+use crate::verify::PutSnapshot;
+use crate::window::SaleWindow;
+
+/// Energy set aside for every `put` a sale may perform, when checking whether a trip is affordable
+pub const PUT_ENERGY_ESTIMATE: usize = 5;
+
+/// The tool; its associated functions can be used right away, while an instance also keeps track
+/// of the Markets the robot knows about
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::SwiftSeller;
+/// let mut seller = SwiftSeller::new();
+/// ```
+///
+/// # Notes
+/// - pass every event from `handle_event` to `SwiftSeller::handle_event` to keep the Market records fresh
+/// - since 0.2 it is no longer a unit struct, create it with `SwiftSeller::new`
+#[derive(Default)]
+pub struct SwiftSeller {
+    registry: MarketRegistry,
+    observers: Vec<Box<dyn SaleObserver>>,
+    prices: PriceBook,
+    options: SaleOptions,
+    stash: Stash,
+    quota: DailyQuota,
+    broker: Option<(MarketBroker, RobotId)>,
+    tracing: bool,
+    last_trace: Option<SaleTrace>,
+}
 impl Tools for SwiftSeller {}
 
+// The observers can't be printed, only how many there are
+impl fmt::Debug for SwiftSeller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwiftSeller")
+            .field("registry", &self.registry)
+            .field("observers", &self.observers.len())
+            .field("prices", &self.prices)
+            .field("options", &self.options)
+            .field("stash", &self.stash)
+            .field("quota", &self.quota)
+            .field("broker", &self.broker)
+            .field("tracing", &self.tracing)
+            .field("last_trace", &self.last_trace)
+            .finish()
+    }
+}
+
 impl SwiftSeller {
+    pub fn new() -> Self {
+        SwiftSeller::default()
+    }
+
+    /// The Markets this seller knows about
+    pub fn markets(&self) -> &MarketRegistry {
+        &self.registry
+    }
+
+    pub fn markets_mut(&mut self) -> &mut MarketRegistry {
+        &mut self.registry
+    }
+
+    /// The prices this seller expects, learnt from every sale made through `sell`
+    pub fn prices(&self) -> &PriceBook {
+        &self.prices
+    }
+
+    pub fn prices_mut(&mut self) -> &mut PriceBook {
+        &mut self.prices
+    }
+
+    /// How the sales made through `sell` are carried out
+    pub fn options(&self) -> &SaleOptions {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut SaleOptions {
+        &mut self.options
+    }
+
+    /// Sets what the sales made through `sell` are meant to achieve, everything by default
+    ///
+    /// # Notes
+    /// - `SaleGoal::FreeSlots` sells nothing with the default prices, the items are skipped with
+    ///   `SkipReason::CannotFreeSpace`, see its documentation
+    pub fn set_goal(&mut self, goal: SaleGoal) {
+        self.options.goal = goal;
+    }
+
+    /// Splits every item in chunks whose coins fit the backpack, `None` (the default) to offer it whole
+    pub fn set_chunking(&mut self, chunking: Option<Chunking>) {
+        self.options.chunking = chunking;
+    }
+
+    /// Updates the Market records with an event the robot received
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `event`: The event, as passed on from the robot's `handle_event`
+    ///
+    /// # Notes
+    /// - Markets reported by `TileContentUpdated` are recorded with their interactions left, so
+    ///   route plans stay accurate without scanning the surroundings again
+    /// - `DayChanged` starts a new day for the quota
+    pub fn handle_event(&mut self, event: &Event) {
+        self.registry.observe(event);
+        self.quota.observe(event);
+    }
+
+    /// Limits what the sales made through `sell` sell every day, unlimited by default
+    pub fn set_quota(&mut self, quota: DailyQuota) {
+        self.quota = quota;
+    }
+
+    /// The daily limits and what has been sold today
+    pub fn quota(&self) -> &DailyQuota {
+        &self.quota
+    }
+
+    /// Auto-sell to an adjacent Market, like `swift_seller`, and update the Market records
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `vec`: The items you want the tool to auto-sell
+    ///
+    /// # Returns
+    /// - `SaleReport`: The items sold at the Market, the coins earned and the interactions used
+    /// - `LibError`: The error that occurred, see `swift_seller`
+    ///
+    /// # Notes
+    /// - every registered `SaleObserver` is notified of each step of the sale
+    /// - only what the goal set with `set_goal` needs is sold, in the order of `vec`
+    /// - with `SaleOptions::stash_leftovers`, when the sale runs short of space for the coins, the
+    ///   items of `vec` left unsold are put in adjacent Crates
+    /// - with `SaleOptions::verify`, every `put` is checked and the broken invariants are reported
+    /// - outside the window set in `SaleOptions::window` nothing is sold and `OperationNotAllowed` is returned
+    /// - nothing past the daily quota set with `set_quota` is sold
+    /// - what was sold before an error, such as `NotEnoughSpace`, still counts towards the quota and
+    ///   is taken off the robot's reservations
+    /// - with a broker set through `set_broker`, `OperationNotAllowed` is returned unless the robot
+    ///   holds interactions at an adjacent Market
+    pub fn sell(
+        &mut self,
+        robot: &mut impl Runnable,
+        world: &mut World,
+        vec: Vec<Content>
+    ) -> Result<SaleReport, LibError> {
+        self.sell_with(robot, world, vec, None, None)
+    }
+
+    /// `sell`, to the Market at `only` if given, notifying `extra` too
+    fn sell_with(
+        &mut self,
+        robot: &mut impl Runnable,
+        world: &mut World,
+        vec: Vec<Content>,
+        only: Option<(usize, usize)>,
+        extra: Option<&mut dyn SaleObserver>
+    ) -> Result<SaleReport, LibError> {
+        let mut options = self.options.clone();
+        let mut quota = self.quota.clone();
+        if let Some(market) = only {
+            options.markets = options.markets.restricted_to(market);
+        }
+
+        // Sharing the Markets, sell only where the robot holds interactions, and no more than it holds
+        if let Some((broker, id)) = &self.broker {
+            let markets = adjacency::find_adjacent(robot, world, |tile| matches!(tile.content, Content::Market(n) if n > 0));
+            let held = markets.iter()
+                .filter(|market| options.markets.allows(market.coordinate))
+                .map(|market| (market.coordinate, broker.reserved_by(*id, market.coordinate)))
+                .filter(|(_, reserved)| *reserved > 0)
+                .max_by_key(|(_, reserved)| *reserved);
+            let Some((market, reserved)) = held else {
+                debug!("no interactions reserved at the markets next to the robot: robot={}", id);
+                return Err(LibError::OperationNotAllowed);
+            };
+            for adjacent in markets.iter().filter(|adjacent| adjacent.coordinate != market) {
+                options.markets = options.markets.with_denied(adjacent.coordinate);
+            }
+            quota = quota.with_reservation(reserved);
+        }
+
+        let mut trace = if self.tracing { Some(SaleTrace::default()) } else { None };
+        let mut space = ShortOfSpace::default();
+
+        let mut observers: Vec<&mut dyn SaleObserver> = self.observers.iter_mut()
+            .map(|observer| observer.as_mut() as &mut dyn SaleObserver)
+            .collect();
+        observers.push(&mut space);
+        if let Some(trace) = trace.as_mut() {
+            observers.push(trace);
+        }
+        if let Some(extra) = extra {
+            observers.push(extra);
+        }
+
+        let items = vec.clone();
+        // A sale that fails halfway still sold something, which has to be accounted for
+        let (mut report, error) = match SwiftSeller::sell_adjacent(robot, world, vec, &options, &quota, &mut self.prices, &mut observers) {
+            Ok(report) => (report, None),
+            Err((e, report)) => (*report, Some(e))
+        };
+        if options.stash_leftovers && space.0 {
+            report.stashed = stash::stash_items(robot, world, &items, &mut self.stash);
+        }
+        self.registry.refresh(world);
+        self.quota.record(&report);
+        if let (Some((broker, id)), Some(market)) = (&self.broker, report.market) {
+            broker.consume(*id, market, report.interactions_used);
+            if let Some(interactions) = self.registry.interactions(market) {
+                broker.update(market, interactions);
+            }
+        }
+        if trace.is_some() {
+            self.last_trace = trace;
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(report)
+        }
+    }
+
+    /// Sell to an adjacent Market, depositing the coins in an adjacent Bank whenever they fill the backpack
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `vec`: The items you want the tool to auto-sell
+    ///
+    /// # Returns
+    /// - `BankReport`: Every sale made, and the coins banked versus the coins kept in the backpack
+    /// - `LibError`: The error that occurred
+    ///
+    /// # Errors
+    /// - any error `sell` returns before a single item could be sold
+    /// - `NotEnoughSpace`: Nothing could be sold, and the Bank took no coins to make room
+    ///
+    /// # Notes
+    /// - the coins already in the backpack are deposited first, to make room
+    /// - when a sale runs out of room for the coins, they are deposited and the sale starts again
+    /// - without a Bank next to the robot it sells once, like `sell`
+    pub fn sell_and_bank(
+        &mut self,
+        robot: &mut impl Runnable,
+        world: &mut World,
+        vec: Vec<Content>
+    ) -> Result<BankReport, LibError> {
+        let mut report = BankReport::default();
+        let mut banking = bank::adjacent_bank(robot, world).is_some();
+
+        if banking && bank::coins_held(robot) > 0 {
+            banking = SwiftSeller::bank_coins(robot, world, &mut report);
+        }
+
+        loop {
+            let held_before = robot.get_backpack().get_contents().clone();
+            let coins_before = bank::coins_held(robot);
+
+            let mut trace = SaleTrace::default();
+            let result = self.sell_with(robot, world, vec.clone(), None, Some(&mut trace));
+
+            // Count what was sold from the backpack, a sale that runs out of room reports nothing
+            let held_after = robot.get_backpack().get_contents();
+            let mut sold_now: usize = 0;
+            for (item, before) in &held_before {
+                if prices::sellable_kind(item).is_some() {
+                    let sold = before.saturating_sub(held_after.get(item).copied().unwrap_or(0));
+                    if sold > 0 {
+                        *report.items_sold.entry(item.clone()).or_insert(0) += sold;
+                        sold_now += sold;
+                    }
+                }
+            }
+            report.coins_earned += bank::coins_held(robot).saturating_sub(coins_before);
+
+            let mut failure = None;
+            let out_of_room = match result {
+                Ok(sale) => {
+                    report.sales.push(sale);
+                    trace.entries.iter().any(|entry| matches!(
+                        entry,
+                        TraceEntry::Skipped { reason: SkipReason::NoSpaceForCoins, .. }
+                    ))
+                },
+                Err(LibError::NotEnoughSpace(tried)) if banking => {
+                    failure = Some(LibError::NotEnoughSpace(tried));
+                    true
+                },
+                Err(e) => {
+                    if report.items_sold.is_empty() {
+                        return Err(e);
+                    }
+                    break;
+                }
+            };
+            debug!("sale and bank round: sold={} out_of_room={} banking={}", sold_now, out_of_room, banking);
+
+            // Make room and sell again, as long as the Bank takes the coins
+            if !(out_of_room && banking) {
+                break;
+            }
+            banking = SwiftSeller::bank_coins(robot, world, &mut report);
+            if !banking {
+                // No room could be made, a sale that sold nothing failed
+                if let Some(e) = failure.filter(|_| report.items_sold.is_empty()) {
+                    return Err(e);
+                }
+                break;
+            }
+        }
+
+        if banking && bank::coins_held(robot) > 0 {
+            SwiftSeller::bank_coins(robot, world, &mut report);
+        }
+        report.coins_kept = bank::coins_held(robot);
+        info!("sale and bank complete: sold={:?} coins={} banked={} kept={}", report.items_sold, report.coins_earned, report.coins_banked, report.coins_kept);
+        Ok(report)
+    }
+
+    /// Deposit the coins held in the adjacent Bank, `false` once it takes no more
+    fn bank_coins(robot: &mut impl Runnable, world: &mut World, report: &mut BankReport) -> bool {
+        match bank::deposit_coins(robot, world) {
+            Ok((bank, coins)) if coins > 0 => {
+                report.bank = Some(bank);
+                report.coins_banked += coins;
+                true
+            },
+            _ => false
+        }
+    }
+
+    /// Shares the Markets with other robots: `sell` only sells to a Market where the robot holds
+    /// interactions, uses no more than it holds and takes them off its reservation, see
+    /// `broker::MarketBroker`
+    pub fn set_broker(&mut self, broker: MarketBroker, robot: RobotId) {
+        self.broker = Some((broker, robot));
+    }
+
+    pub fn broker(&self) -> Option<&(MarketBroker, RobotId)> {
+        self.broker.as_ref()
+    }
+
+    /// The items left in Crates by the sales made through `sell`
+    pub fn stash(&self) -> &Stash {
+        &self.stash
+    }
+
+    pub fn stash_mut(&mut self) -> &mut Stash {
+        &mut self.stash
+    }
+
+    /// Records a `SaleTrace` of every sale made through `sell`, see `last_trace`
+    pub fn set_tracing(&mut self, tracing: bool) {
+        self.tracing = tracing;
+    }
+
+    /// The trace of the latest sale made through `sell` while tracing was on
+    pub fn last_trace(&self) -> Option<&SaleTrace> {
+        self.last_trace.as_ref()
+    }
+
+    /// Registers an observer, notified of every step of the sales made through `sell`
+    pub fn add_observer(&mut self, observer: Box<dyn SaleObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Plan a route through the Markets this seller knows about and may use, see `route::plan_route`
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `order`: The items to sell and their quantity
+    /// - `prices`: The expected price of every item
+    /// - `energy_budget`: The energy the whole route may spend
+    pub fn plan_route(
+        &self,
+        robot: &impl Runnable,
+        world: &World,
+        order: &HashMap<Content, usize>,
+        prices: &PriceBook,
+        energy_budget: usize
+    ) -> Result<RoutePlan, LibError> {
+        let map = robot_map(world).ok_or(LibError::OperationNotAllowed)?;
+        let start = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        route::plan_route(
+            &map,
+            start,
+            &self.registry.usable(),
+            &self.options.markets,
+            order,
+            prices,
+            &look_at_sky(world).get_weather_condition(),
+            energy_budget
+        )
+    }
+
     /// Auto-sell to a Market only the items you choose
     ///
     /// # Usage
@@ -31,258 +486,2288 @@ impl SwiftSeller {
     ///
     /// # Notes
     /// - if the market's interaction get to 0 between the sale of multiple valid items, the map containing the items sold up to that point is returned
-    /// - does not support multiple robots
+    /// - does not coordinate multiple robots, see `broker::MarketBroker` and `SwiftSeller::set_broker`
     pub fn swift_seller(
         robot: &mut impl Runnable,
         world: &mut World,
         vec: Vec<Content>
     ) -> Result<HashMap<Content, usize>, LibError> {
+        SwiftSeller::sell_adjacent(robot, world, vec, &SaleOptions::default(), &DailyQuota::default(), &mut PriceBook::default(), &mut [])
+            .map(|report| report.items_sold)
+            .map_err(|(e, _)| e)
+    }
 
-        // First of all, let's check if the robot happens to be near a tile with a Market on it
+    /// Auto-sell like `swift_seller`, to the Market at a given coordinate only
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `market`: The coordinate of the Market to sell to
+    /// - `vec`: The items you want the tool to auto-sell
+    ///
+    /// # Errors
+    /// - `OperationNotAllowed`: The Market at `market` is not next to the robot or has 0 interactions left
+    /// - the other errors `swift_seller` returns
+    pub fn swift_seller_at(
+        robot: &mut impl Runnable,
+        world: &mut World,
+        market: (usize, usize),
+        vec: Vec<Content>
+    ) -> Result<HashMap<Content, usize>, LibError> {
+        let options = SaleOptions {
+            markets: MarketFilter::new().with_allowed(market),
+            ..SaleOptions::default()
+        };
+        SwiftSeller::sell_adjacent(robot, world, vec, &options, &DailyQuota::default(), &mut PriceBook::default(), &mut [])
+            .map(|report| report.items_sold)
+            .map_err(|(e, _)| e)
+    }
 
-        let mut market_near: bool = false;
-        let mut market_dir = Direction::Left; // initialized
-        let mut interactions_left: usize = 0;
-        let mut highest_interactions: usize = 0;
-
-        let robot_view = robot_view(robot, &world);
-        for (i, row) in robot_view.iter().enumerate() {
-            for (j, col) in row.iter().enumerate() {
-                match (i, j) {
-                    (0, 1) | (1, 0) | (1, 2) | (2, 1) => {
-                        match col {
-                            | None => (),
-                            | Some(tile) => {
-                                match tile.content {
-                                    Content::Market(n) => {
-                                        if n > highest_interactions {
-                                            interactions_left = n;
-                                            highest_interactions = n;
-
-                                            market_near = true;
-                                            match (i, j) {
-                                                (0, 1) => market_dir = Direction::Up,
-                                                (1, 0) => market_dir = Direction::Left,
-                                                (1, 2) => market_dir = Direction::Right,
-                                                (2, 1) => market_dir = Direction::Down,
-                                                _ => return Err(LibError::OperationNotAllowed)
-                                            }
+    /// `swift_seller_at`, selling no more than the given quantity of each item, using no more than
+    /// the `reserved` interactions if given, and handing back what was sold before an error
+    pub(crate) fn sell_quantities_at(
+        robot: &mut impl Runnable,
+        world: &mut World,
+        market: (usize, usize),
+        items: &[(Content, usize)],
+        reserved: Option<usize>
+    ) -> Result<SaleReport, (LibError, Box<SaleReport>)> {
+        let options = SaleOptions {
+            markets: MarketFilter::new().with_allowed(market),
+            ..SaleOptions::default()
+        };
+        let quota = items.iter()
+            .fold(DailyQuota::new(), |quota, (item, qty)| quota.with_item_limit(item.clone(), *qty));
+        let quota = match reserved {
+            Some(reserved) => quota.with_reservation(reserved),
+            None => quota
+        };
+        let vec = items.iter().map(|(item, _)| item.clone()).collect();
+        SwiftSeller::sell_adjacent(robot, world, vec, &options, &quota, &mut PriceBook::default(), &mut [])
+    }
 
-                                        }
-                                    },
-                                    _ => ()
-                                }
-                            }
-                        }
-                    },
-                    _ => ()
-                }
+    /// Auto-sell like `swift_seller`, recording every decision taken along the way
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `vec`: The items you want the tool to auto-sell
+    ///
+    /// # Returns
+    /// - the outcome `swift_seller` would have returned
+    /// - `SaleTrace`: The decisions taken, print it for a readable explanation
+    pub fn swift_seller_traced(
+        robot: &mut impl Runnable,
+        world: &mut World,
+        vec: Vec<Content>
+    ) -> (Result<HashMap<Content, usize>, LibError>, SaleTrace) {
+        let mut trace = SaleTrace::default();
+        let result = SwiftSeller::sell_adjacent(robot, world, vec, &SaleOptions::default(), &DailyQuota::default(), &mut PriceBook::default(), &mut [&mut trace])
+            .map(|report| report.items_sold)
+            .map_err(|(e, _)| e);
+        (result, trace)
+    }
+
+    /// The sale behind `swift_seller`, carried out as `options` say and notifying `observers` of every step
+    fn sell_adjacent(
+        robot: &mut impl Runnable,
+        world: &mut World,
+        vec: Vec<Content>,
+        options: &SaleOptions,
+        quota: &DailyQuota,
+        prices: &mut PriceBook,
+        observers: &mut [&mut dyn SaleObserver]
+    ) -> Result<SaleReport, (LibError, Box<SaleReport>)> {
+
+        // Sell only when the time of day and the weather allow it
+        if !options.window.can_sell(&look_at_sky(world)) {
+            debug!("outside the sale window");
+            for observer in observers.iter_mut() {
+                observer.on_stop(&StopReason::OutsideWindow);
             }
+            return Err((LibError::OperationNotAllowed, Box::default()));
         }
 
+        // First of all, let's check if the robot happens to be near a tile with a Market on it
+
+        let position = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        let view = robot_view(robot, world);
+        for (direction, tile) in adjacency::neighbours_in(&view) {
+            for observer in observers.iter_mut() {
+                observer.on_neighbour(&direction, tile);
+            }
+        }
+        // Among the adjacent Markets that may be used, pick a preferred one, then the one with most
+        // interactions left, then the first one looked at
+        let interactions = |tile: &Tile| match tile.content {
+            Content::Market(n) => n,
+            _ => 0
+        };
+        let candidates: Vec<adjacency::Adjacent> = adjacency::find_in(&view, position, |tile| interactions(tile) > 0).into_iter()
+            .filter(|market| options.markets.allows(market.coordinate))
+            .collect();
+        let selected = candidates.iter()
+            .rev()
+            .max_by_key(|market| (options.markets.prefers(market.coordinate), interactions(&market.tile)))
+            .cloned();
+        let reason = match &selected {
+            _ if candidates.len() == 1 => SelectionReason::OnlyCandidate,
+            Some(adjacent) if options.markets.prefers(adjacent.coordinate) => SelectionReason::Preferred,
+            _ => SelectionReason::MostInteractions
+        };
+        let market_near = selected.is_some();
+        let (market_dir, market, mut interactions_left) = match selected {
+            Some(adjacent) => (adjacent.direction, adjacent.coordinate, interactions(&adjacent.tile)),
+            None => (Direction::Left, position, 0)
+        };
+
         // If the robot is NOT near a tile with a Market on it this tool cannot be used
         if !market_near {
-            return Err(LibError::OperationNotAllowed);
+            debug!("no market next to the robot");
+            for observer in observers.iter_mut() {
+                observer.on_stop(&StopReason::NoMarketNearby);
+            }
+            return Err((LibError::OperationNotAllowed, Box::default()));
         }
         // Straight off the bat, if the market has no interactions left, quit
         if interactions_left < 1 {
-            return Err(LibError::OperationNotAllowed);
+            debug!("the market next to the robot has no interactions left");
+            for observer in observers.iter_mut() {
+                observer.on_stop(&StopReason::MarketEmpty);
+            }
+            return Err((LibError::OperationNotAllowed, Box::default()));
         }
 
-        // If the robot is near a Market, sell the items held in its backpack which can be sold
+        debug!("market selected: coordinate={:?} direction={:?} interactions={}", market, market_dir, interactions_left);
+        for observer in observers.iter_mut() {
+            observer.on_market_selected(market, &market_dir, interactions_left, &reason);
+        }
 
-        let mut _coins_earned: usize = 0;
+        // If the robot is near a Market, sell the items held in its backpack which can be sold
 
-        let mut items_sold: HashMap<Content, usize> = HashMap::new();
+        let mut report = SaleReport {
+            market: Some(market),
+            ..SaleReport::default()
+        };
 
         let cloned_contents = robot.get_backpack().get_contents().clone();
 
         // If the tool sells at least one item, return it when the interactions left get to 0
         let mut sold_anything:bool = false;
 
-        // Sell items in order given by the user
-        for items in vec {
+        // Unless the goal is reached first, the sale stops when the list is over
+        let mut stop_reason = StopReason::ListExhausted;
+
+        let goal = &options.goal;
+
+        // Sell items in order given by the user, unless the goal needs otherwise
+        let vec = goal.order(vec, robot.get_backpack(), prices);
+        // Every item held after the one being sold needs an interaction too
+        let pending: Vec<usize> = (0..vec.len())
+            .map(|index| vec[index + 1..].iter().filter(|item| cloned_contents.get(item).is_some_and(|qty| *qty > 0)).count())
+            .collect();
+        'items: for (index, items) in vec.into_iter().enumerate() {
+            if goal.is_reached(&report, robot.get_backpack()) {
+                debug!("goal reached: goal={:?}", goal);
+                stop_reason = StopReason::GoalReached;
+                break;
+            }
             // Allow selling only the items that can actually be sold
             match items {
                 Content::Rock(_) | Content::Fish(_) | Content::Tree(_) => {
+                    let mut held = false;
                     for (item, qty) in cloned_contents.clone() {
-                        if interactions_left < 1 {
+                        if items == item && qty > 0 && interactions_left < 1 {
+                            debug!("market out of interactions: item={:?}", items);
+                            for observer in observers.iter_mut() {
+                                observer.on_skip(&items, &SkipReason::NoInteractionsLeft);
+                                observer.on_stop(&StopReason::OutOfInteractions);
+                            }
                             return if sold_anything {
-                                Ok(items_sold)
+                                info!("sale complete: sold={:?} coins={} interactions={}", report.items_sold, report.coins_earned, report.interactions_used);
+                                for observer in observers.iter_mut() {
+                                    observer.on_complete(&report);
+                                }
+                                Ok(report)
                             } else {
-                                Err(LibError::OperationNotAllowed)
+                                Err((LibError::OperationNotAllowed, Box::new(report)))
                             }
                         }
                         if items == item && qty > 0 {
-                            match put(
-                                robot,
-                                world,
-                                item.clone(),
-                                qty,
-                                market_dir.clone()
-                            ) {
-                                Ok(earned) => {
-                                    _coins_earned += earned;
-                                    let sold = qty - robot.get_backpack().get_contents().clone().get(&item).unwrap();
-                                    items_sold.insert(item, sold);
-                                    interactions_left -= 1;
-                                    sold_anything = true;
-                                },
-                                Err(LibError::NotEnoughSpace(tried)) => {
-                                    return Err(LibError::NotEnoughSpace(tried));
-                                },
-                                Err(e) => {
-                                    eprintln!("ERR: {:?} - PUT arguments: {:?} {:?} {:?}", e, item.clone(), qty, market_dir.clone());
-                                    panic!("UNEXPECTED ERROR - CONTACT THE GROUP")
+                            held = true;
+                            let offer = goal.quantity(&item, qty, &report, robot.get_backpack(), prices);
+                            if offer == 0 {
+                                // Making room is needed, but selling this item can't make any
+                                let reason = match goal {
+                                    SaleGoal::FreeSlots(_) if !goal::frees_slots(&item, prices) => SkipReason::CannotFreeSpace,
+                                    _ => SkipReason::NotNeeded
+                                };
+                                debug!("skipped: item={:?} reason={:?}", item, reason);
+                                for observer in observers.iter_mut() {
+                                    observer.on_skip(&item, &reason);
+                                }
+                                continue;
+                            }
+                            // Never go past what is left of today's quota
+                            let offer = match quota.remaining_items(&item) {
+                                Some(remaining) => offer.min(remaining.saturating_sub(report.items_sold.get(&item).copied().unwrap_or(0))),
+                                None => offer
+                            };
+                            if offer == 0 {
+                                debug!("skipped, daily quota reached: item={:?}", item);
+                                for observer in observers.iter_mut() {
+                                    observer.on_skip(&item, &SkipReason::QuotaReached);
+                                }
+                                continue;
+                            }
+                            // The offer may be split in chunks, each taking one interaction
+                            let mut left = offer;
+                            while left > 0 {
+                                if interactions_left < 1 {
+                                    debug!("market out of interactions: item={:?} left={}", item, left);
+                                    stop_reason = StopReason::OutOfInteractions;
+                                    break 'items;
+                                }
+                                if quota.remaining_interactions().is_some_and(|remaining| report.interactions_used >= remaining) {
+                                    debug!("daily interactions quota reached: item={:?} left={}", item, left);
+                                    stop_reason = StopReason::QuotaReached;
+                                    break 'items;
+                                }
+                                if quota.reserved().is_some_and(|reserved| report.interactions_used >= reserved) {
+                                    debug!("reserved interactions used up: item={:?} left={}", item, left);
+                                    stop_reason = StopReason::ReservationExhausted;
+                                    break 'items;
+                                }
+                                let chunk = match &options.chunking {
+                                    Some(chunking) => chunking.chunk(&item, left, interactions_left.saturating_sub(pending[index]), robot.get_backpack(), prices),
+                                    None => left
+                                };
+                                if chunk == 0 {
+                                    debug!("skipped, no space for the coins: item={:?} left={}", item, left);
+                                    for observer in observers.iter_mut() {
+                                        observer.on_skip(&item, &SkipReason::NoSpaceForCoins);
+                                    }
+                                    break;
+                                }
+
+                                for observer in observers.iter_mut() {
+                                    observer.before_put(&item, chunk);
+                                }
+                                // Both snapshots read the Market's tile, the counter may be stale
+                                let view = robot_view(robot, world);
+                                let shown = adjacency::tile_towards(&view, &market_dir).map_or(0, interactions);
+                                let before = PutSnapshot::take(robot, &item, shown);
+                                let result = put(
+                                    robot,
+                                    world,
+                                    item.clone(),
+                                    chunk,
+                                    market_dir.clone()
+                                );
+                                for observer in observers.iter_mut() {
+                                    observer.after_put(&item, chunk, &result);
+                                }
+                                match result {
+                                    Ok(earned) => {
+                                        // Someone else may be using the Market too, trust the tile over the counter
+                                        let view = robot_view(robot, world);
+                                        let actual = adjacency::tile_towards(&view, &market_dir).map_or(0, interactions);
+                                        let after = PutSnapshot::take(robot, &item, actual);
+
+                                        report.coins_earned += earned;
+                                        let sold = before.held - after.held;
+                                        debug!("sold: item={:?} qty={} coins={} direction={:?}", item, sold, earned, market_dir);
+                                        prices.record_sale(&item, sold, earned);
+                                        for observer in observers.iter_mut() {
+                                            observer.on_accepted(&item, chunk, sold, earned);
+                                        }
+                                        *report.items_sold.entry(item.clone()).or_insert(0) += sold;
+                                        report.interactions_used += 1;
+                                        interactions_left -= 1;
+                                        sold_anything = true;
+                                        // The Market may take less than offered, what it left is offered again
+                                        left = left.saturating_sub(sold);
+                                        // unless it took nothing, offering the same again would never end
+                                        if sold == 0 {
+                                            debug!("skipped, the market took nothing: item={:?} left={}", item, left);
+                                            for observer in observers.iter_mut() {
+                                                observer.on_skip(&item, &SkipReason::NothingTaken);
+                                            }
+                                            left = 0;
+                                        }
+
+                                        if options.verify {
+                                            for violation in verify::check_put(&item, chunk, sold, earned, &before, &after) {
+                                                error!("invariant broken: {}", violation);
+                                                report.violations.push(violation);
+                                            }
+                                        }
+                                        if actual != interactions_left {
+                                            warn!("market changed behind the seller: market={:?} expected={} actual={}", market, interactions_left, actual);
+                                            report.surprises.push(MarketSurprise {
+                                                item: item.clone(),
+                                                expected: interactions_left,
+                                                actual,
+                                            });
+                                            interactions_left = actual;
+                                        }
+                                    },
+                                    Err(LibError::NotEnoughSpace(tried)) => {
+                                        info!("not enough space for the coins: item={:?} qty={} coins={}", item, chunk, tried);
+                                        for observer in observers.iter_mut() {
+                                            observer.on_stop(&StopReason::NotEnoughSpace(tried));
+                                        }
+                                        // Hand back what was sold before, so that it is still accounted for
+                                        return Err((LibError::NotEnoughSpace(tried), Box::new(report)));
+                                    },
+                                    Err(e) => {
+                                        error!("unexpected put error: error={:?} item={:?} qty={} direction={:?}", e, item, chunk, market_dir);
+                                        panic!("UNEXPECTED ERROR - CONTACT THE GROUP")
+                                    }
                                 }
                             }
                         }
                     }
+                    if !held {
+                        debug!("skipped, not in the backpack: item={:?}", items);
+                        for observer in observers.iter_mut() {
+                            observer.on_skip(&items, &SkipReason::NotInBackpack);
+                        }
+                    }
+                }
+                _ => {
+                    debug!("skipped, not sellable: item={:?}", items);
+                    for observer in observers.iter_mut() {
+                        observer.on_skip(&items, &SkipReason::NotSellable);
+                    }
                 }
-                _ => ()
             }
         }
-        Ok(items_sold)
-    }
-}
+        info!("sale complete: sold={:?} coins={} interactions={}", report.items_sold, report.coins_earned, report.interactions_used);
+        for observer in observers.iter_mut() {
+            observer.on_stop(&stop_reason);
+            observer.on_complete(&report);
+        }
+        Ok(report)
+    }
+
+    /// Find the closest Market the robot knows about that can still be used
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    ///
+    /// # Returns
+    /// - `Some((coordinate, distance))`: The Market's coordinate and the number of steps needed to stand next to it
+    /// - `None`: The robot doesn't know of any reachable Market with interactions left
+    ///
+    /// # Notes
+    /// - only the tiles discovered by the robot (`robot_map`) are considered
+    /// - the distance is walkable distance, impassable tiles such as deep water and lava are avoided
+    pub fn nearest_market(
+        robot: &impl Runnable,
+        world: &World
+    ) -> Option<((usize, usize), usize)> {
+        SwiftSeller::nearest_allowed_market(robot, world, &MarketFilter::default())
+    }
+
+    /// `nearest_market`, among the Markets `filter` allows
+    ///
+    /// # Notes
+    /// - the closest preferred Market is returned if one can be reached, however far
+    pub fn nearest_allowed_market(
+        robot: &impl Runnable,
+        world: &World,
+        filter: &MarketFilter
+    ) -> Option<((usize, usize), usize)> {
+
+        let map = robot_map(world)?;
+        let markets = filter.apply(navigation::known_markets(&map));
+        let preferred: Vec<((usize, usize), usize)> = markets.iter()
+            .filter(|(market, _)| filter.prefers(*market))
+            .cloned()
+            .collect();
+
+        let start = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        [preferred, markets].into_iter()
+            .filter(|markets| !markets.is_empty())
+            .find_map(|markets| {
+                let (end, path) = navigation::shortest_path(&map, start, |position| {
+                    markets.iter().any(|(market, _)| navigation::is_adjacent(*market, position))
+                })?;
+
+                // More than one Market may be next to the destination, pick the one with most interactions
+                markets.into_iter()
+                    .filter(|(market, _)| navigation::is_adjacent(*market, end))
+                    .max_by_key(|(_, interactions)| *interactions)
+                    .map(|(market, _)| (market, path.len()))
+            })
+    }
+
+    /// Walk next to a known Market and auto-sell to it only the items you choose
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `market`: The coordinate of the Market to sell to, usually obtained through `nearest_market`
+    /// - `vec`: The items you want the tool to auto-sell
+    ///
+    /// # Returns
+    /// - `HashMap<Content, usize>`: The items sold at the Market and their quantity
+    /// - `LibError`: The error that occurred
+    ///
+    /// # Errors
+    /// - `OperationNotAllowed`: The robot doesn't know of a Market with interactions left at `market`
+    /// - `CannotWalk`: The Market cannot be reached through the tiles known to the robot
+    /// - `NotEnoughEnergy`: The robot cannot afford both the trip and the sale
+    /// - any error `swift_seller_at` may return once the robot is next to the Market
+    ///
+    /// # Notes
+    /// - the trip is planned to spend as little energy as possible, see `planner::plan_trip`
+    /// - the energy check happens before the robot moves, so a refused trip costs nothing
+    /// - the sale is estimated at `PUT_ENERGY_ESTIMATE` per item to sell
+    /// - only `market` is sold to, even if another Market is next to the robot too
+    /// - no `MarketFilter` applies and no interactions are reserved, see `walk_and_sell` to follow a
+    ///   seller's `SaleOptions` and broker
+    pub fn go_and_sell(
+        robot: &mut impl Runnable,
+        world: &mut World,
+        market: (usize, usize),
+        vec: Vec<Content>
+    ) -> Result<HashMap<Content, usize>, LibError> {
+        SwiftSeller::walk_to_market(robot, world, market, vec.len(), &SaleWindow::default(), None)?;
+        SwiftSeller::swift_seller_at(robot, world, market, vec)
+    }
+
+    /// Walk next to a known Market and sell to it, like `go_and_sell`, the way `sell` does
+    ///
+    /// # Usage
+    /// ```rust
+    /// use swift_seller::SwiftSeller;
+    /// ```
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `market`: The coordinate of the Market to sell to
+    /// - `vec`: The items you want the tool to auto-sell
+    ///
+    /// # Returns
+    /// - `SaleReport`: The items sold at the Market, the coins earned and the interactions used
+    /// - `LibError`: The error that occurred
+    ///
+    /// # Errors
+    /// - `OperationNotAllowed`: `SaleOptions::markets` doesn't allow the Market
+    /// - `OperationNotAllowed`: The time of day or the weather doesn't allow selling, see
+    ///   `SaleOptions::window`; the robot doesn't move, call it again later
+    /// - `OperationNotAllowed`: The weather doesn't allow walking, see `SaleOptions::window`; the
+    ///   robot stops where it is
+    /// - `OperationNotAllowed`: With a broker set through `set_broker`, no interaction could be
+    ///   reserved at the Market; the robot doesn't move
+    /// - the errors `go_and_sell` returns before the sale, and the errors `sell` returns
+    ///
+    /// # Notes
+    /// - the weather is looked at before every step, the trip stops as soon as it turns
+    /// - only `market` is sold to, even if another Market is next to the robot too
+    /// - with a broker, an interaction per item is reserved before the trip, for `broker::TRIP_TTL`
+    pub fn walk_and_sell(
+        &mut self,
+        robot: &mut impl Runnable,
+        world: &mut World,
+        market: (usize, usize),
+        vec: Vec<Content>
+    ) -> Result<SaleReport, LibError> {
+        if !self.options.markets.allows(market) {
+            return Err(LibError::OperationNotAllowed);
+        }
+        let broker = self.broker.as_ref().map(|(broker, id)| (broker, *id));
+        SwiftSeller::walk_to_market(robot, world, market, vec.len(), &self.options.window, broker)?;
+        self.sell_with(robot, world, vec, Some(market), None)
+    }
+
+    /// Walk next to `market` along the cheapest trip that leaves enough energy to sell `items`
+    /// items, as long as `window` allows walking; nothing happens if `window` doesn't allow selling,
+    /// or if `broker` is given and no interaction could be reserved
+    fn walk_to_market(
+        robot: &mut impl Runnable,
+        world: &mut World,
+        market: (usize, usize),
+        items: usize,
+        window: &SaleWindow,
+        broker: Option<(&MarketBroker, RobotId)>
+    ) -> Result<(), LibError> {
+
+        // Don't spend the energy of the trip on a sale that can't be made
+        let conditions = look_at_sky(world);
+        if !window.can_sell(&conditions) {
+            debug!("trip postponed, outside the sale window: market={:?} time={:?} weather={:?}", market, conditions.get_time_of_day(), conditions.get_weather_condition());
+            return Err(LibError::OperationNotAllowed);
+        }
+
+        let map = robot_map(world).ok_or(LibError::OperationNotAllowed)?;
+
+        // The Market has to be known and usable
+        let interactions = navigation::market_at(&map, market);
+        if interactions < 1 {
+            return Err(LibError::OperationNotAllowed);
+        }
+
+        // Plan the cheapest trip that still leaves enough energy for the sale
+        let start = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        let sale_energy = items.min(interactions) * PUT_ENERGY_ESTIMATE;
+        let trip = planner::plan_trip(
+            &map,
+            start,
+            |position| navigation::is_adjacent(market, position),
+            &look_at_sky(world).get_weather_condition(),
+            robot.get_energy().get_energy_level(),
+            sale_energy
+        )?;
+
+        // Hold the Market's interactions before spending the energy of the trip
+        if let Some((broker, id)) = broker {
+            if broker.reserve_for_trip(id, market, items, || interactions) == 0 {
+                debug!("trip refused, no interactions reserved: market={:?} robot={}", market, id);
+                return Err(LibError::OperationNotAllowed);
+            }
+        }
+
+        debug!("trip to market: market={:?} steps={} energy={}", market, trip.path.len(), trip.energy);
+        for direction in trip.path {
+            if !window.can_travel(&look_at_sky(world)) {
+                debug!("trip stopped by the weather: market={:?} weather={:?}", market, look_at_sky(world).get_weather_condition());
+                return Err(LibError::OperationNotAllowed);
+            }
+            go(robot, world, direction)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::process::exit;
+    use std::rc::Rc;
+
+    use robotics_lib::energy::Energy;
+    use robotics_lib::event::events::Event;
+
+    use robotics_lib::interface::{Direction, go, where_am_i, destroy };
+
+    use robotics_lib::runner::backpack::BackPack;
+    use robotics_lib::runner::{Robot, Runner};
+
+    use robotics_lib::world::coordinates::Coordinate;
+    use robotics_lib::world::environmental_conditions::{EnvironmentalConditions, WeatherType};
+    use robotics_lib::world::tile::{Tile, TileType};
+    use robotics_lib::world::world_generator::Generator;
+
+    use crate::auto_sell::{AutoSell, SellTrigger};
+    use crate::triggers::{TriggerCondition, TriggerEngine};
+    use crate::explore::explore_for_market;
+    use crate::harvest::harvest_and_sell;
+    use crate::plan::{PlanStatus, SalePlan, SaleStep};
+    use crate::registry::MarketRegistry;
+    use crate::window::SaleWindow;
+    use super::*;
+
+    /**************************************************************************
+    *  MAP:
+    *    ______________________________________
+    *   |            |            |            |
+    *   |    Grass   |   Grass    |   Grass    |
+    *   |    0 el    |   0 el     |    0 el    |
+    *   |    None    |  Tree(3)   |   Tree(4)  |
+    *   |____________|____________|____________|
+    *   |            |            |            |
+    *   |   Shallow  |   Grass    |   Grass    |
+    *   |    0 el    |   0 el     |    0 el    |
+    *   |   Fish(3)  | Market(1)  |   Rock(3)  |
+    *   |____________|____________|____________|
+    *   |            |            |            |
+    *   |    Grass   |    Grass   |   Grass    |
+    *   |    0 el    |    0 el    |    0 el    |
+    *   |   Rock(2)  |    None    |   Rock(6)  |
+    *   |____________|____________|____________|
+    *
+    *   Copyright: comment format courtesy of the common crate
+    */
+
+    fn test_world(market_interactions: usize) -> impl Generator {
+        // World generator
+
+        struct MarketWorld {
+            market_interactions: usize
+        }
+        impl MarketWorld {
+            fn new(market_interactions: usize) -> Self {
+                MarketWorld {
+                    market_interactions
+                }
+            }
+        }
+        impl Generator for MarketWorld {
+            fn gen(&mut self) -> robotics_lib::world::world_generator::World {
+                let mut map: Vec<Vec<Tile>> = Vec::new();
+
+                map.push(Vec::new());
+                map[0].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::None,
+                    elevation: 0,
+                });
+                map[0].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Tree(3),
+                    elevation: 0,
+                });
+                map[0].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Tree(4),
+                    elevation: 0,
+                });
+
+                map.push(Vec::new());
+                map[1].push(Tile {
+                    tile_type: TileType::ShallowWater,
+                    content: Content::Fish(3),
+                    elevation: 0,
+                });
+                map[1].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Market(self.market_interactions),
+                    elevation: 0,
+                });
+                map[1].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Rock(3),
+                    elevation: 0,
+                });
+
+                map.push(Vec::new());
+                map[2].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Rock(2),
+                    elevation: 0,
+                });
+                map[2].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::None,
+                    elevation: 0,
+                });
+                map[2].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Rock(6),
+                    elevation: 0,
+                });
+
+                let environmental_conditions =
+                    EnvironmentalConditions::new(&[WeatherType::Sunny],
+                                                 15,
+                                                 12);
+                (map, (0, 0), environmental_conditions.unwrap(), 100.0, None)
+            }
+        }
+
+        MarketWorld::new(market_interactions)
+    }
+
+    fn start(run: Result<Runner, LibError>) {
+        match run {
+            | Ok(mut r) => {
+                let _ = r.game_tick();
+            }
+            | Err(e) => {
+                println!("{:?}", e);
+                exit(1)
+            },
+        }
+    }
+
+    #[test]
+    fn no_market_nearby() {
+
+        // Generate the test world
+        let mut generator = test_world(0);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // List all the movements I intend to make and the outcomes of the function call
+                // after the corresponding movement
+                let movements:&[Direction] = &[
+                    Direction::Right, Direction::Right,
+                    Direction::Down, Direction::Down,
+                    Direction::Left, Direction::Left,
+                    Direction::Up, Direction::Up
+                ];
+
+                // For each movement, perform the following actions
+                for movement in movements {
+                    // Since I created a world ad hoc, those movements should be possible
+                    go(self, world, movement.clone()).expect("CANNOT MOVE");
+
+                    // Every move I should NOT find a market nearby (or find one with value 0)
+                    assert_eq!(
+                        SwiftSeller::swift_seller(self, world, vec![Content::Fish(0), Content::Tree(0), Content::Rock(0)]),
+                        Err(LibError::OperationNotAllowed)
+                    )
+                }
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        // Since the weather is sunny day, the robot is walking on grass, and it starts with full
+        // energy, I can walk around the Market content all in one tick
+        start(run);
+    }
+
+    #[test]
+    fn sell_empty_backpack() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // List all the movements I intend to make and the outcomes of the function call
+                // after the corresponding movement
+                let movements:&[Direction] = &[
+                    Direction::Right, Direction::Right,
+                    Direction::Down, Direction::Down,
+                    Direction::Left, Direction::Left,
+                    Direction::Up, Direction::Up
+                ];
+
+                // For each movement, perform the following actions
+                for (i, movement) in movements.iter().enumerate() {
+                    // Since I created a world ad hoc, those movements should be possible
+                    go(self, world, movement.clone()).expect("CANNOT MOVE");
+
+                    // When I'm nearby a market, I should try to sell everything; I have an empty
+                    // backpack, so the returned value from the function call should be:
+                    let empty_map:HashMap<Content, usize> = HashMap::new();
+
+                    // Let's check
+                    if i % 2 == 0 {
+
+                        assert_eq!(
+
+                            SwiftSeller::swift_seller(self, world, vec![Content::Fish(0), Content::Tree(0), Content::Rock(0)]),
+                            Ok(empty_map)
+                        )
+                    }
+                }
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        // Since the weather is sunny day, the robot is walking on grass, and it starts with full
+        // energy, I can walk around the Market content all in one tick
+        start(run);
+    }
+
+    #[test]
+    fn destroy_and_sell_items() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // List all the movements I intend to make and the outcomes of the function call
+                // after the corresponding movement
+                let movements:&[Direction] = &[
+                    Direction::Right, Direction::Right,
+                    Direction::Down, Direction::Down,
+                    Direction::Left, Direction::Left,
+                    Direction::Up, Direction::Up
+                ];
+
+                // For each movement, perform the following actions
+                for  movement in movements {
+
+                    println!("--------");
+
+                    println!("Destroy {:?}", movement);
+                    // Since I created a world ad hoc, these destroy() should have enough energy
+                    match destroy(self, world, movement.clone()) {
+                        Ok(_) => (),
+                        Err(error) =>
+                            eprintln!("ERR\n\tERROR: {:?}\n\tMOVEMENT: {:?}\n\tBACKPACK SIZE: {}",
+                                      error,
+                                      movement,
+                                      self.get_backpack().get_size()
+                            )
+                    }
+
+                    // Since I created a world ad hoc, those movements should be possible
+                    println!("Go {:?}", movement);
+                    go(self, world, movement.clone()).expect("CANNOT MOVE");
+
+                    let (robot_view, _) = where_am_i(self, &world);
+
+                    for row in robot_view.iter() {
+                        for col in row.iter() {
+                            match col {
+                                | None => print!(" None "),
+                                | Some(tile) => {
+                                    if tile.content != Content::None {
+                                        print!(" {:?}({:?}) ", tile.tile_type, tile.content)
+                                    }
+                                    else {
+                                        print!(" {:?} ", tile.tile_type)
+                                    }
+                                },
+                            }
+                        }
+                        println!();
+                    }
+
+                    // Now I can finally call the function to interact with the Market
+                    println!("Sell?");
+                    match SwiftSeller::swift_seller(self, world, vec![Content::Fish(0), Content::Tree(0), Content::Rock(0)]) {
+                        Err(LibError::OperationNotAllowed) =>
+                            eprintln!("No Market nearby!"),
+                        Err(LibError::NotEnoughSpace(tried)) =>
+                            eprintln!("Can't hold {} coins!", tried),
+                        Err(any) =>
+                            eprintln!("{:?}", any),
+                        Ok(map) => {
+                            println!("Sold to market:");
+                            for (key, value) in map {
+                                println!("\t- item: {}, qty: {}", key, value)
+                            }
+                        }
+                    }
+                }
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        // Since the weather is sunny day, the robot is walking on grass, and it starts with full
+        // energy, I can walk around the Market content all in one tick
+        start(run);
+    }
+
+    #[test]
+    fn zero_energy() {
+    // Generate the test world
+        let mut generator = test_world(100);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+                println!("{:?}", self.get_energy());
+                match destroy(self, world, Direction::Right) {
+                    Ok(_) => (),
+                    Err(error) =>
+                        eprintln!("ERR\n\tERROR: {:?}\n\tMOVEMENT: {:?}\n\tBACKPACK SIZE: {}",
+                                  error,
+                                  Direction::Right,
+                                  self.get_backpack().get_size()
+                        )
+                }
+                println!("{:?}", self.get_energy());
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+                println!("{:?}", self.get_energy());
+                for _ in 0..498 {
+                    go(self, world, Direction::Right).expect("CANNOT MOVE");
+                    go(self, world, Direction::Left).expect("CANNOT MOVE");
+                }
+                println!("{:?}", self.get_energy());
+                println!("{:?}", self.get_backpack());
+
+                match SwiftSeller::swift_seller(self, world, vec![Content::Tree(0)]) {
+                    Ok(_) => {}
+                    Err(_) => {}
+                }
+                println!("{:?}", self.get_energy());
+                println!("{:?}", self.get_backpack());
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    // After selling some of the items, if the market gets to zero interactions the tool returns
+    // an error, while it should return an empty hashmap/the map of items sold up to that point
+    #[test]
+    fn errore_alessandra() {
+        // Generate the test world
+        let mut generator = test_world(1);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+                // Destroy right to get trees
+                let _ = destroy(self, world, Direction::Right);
+
+                // Move right-right to get near rocks
+                let _ = go(self, world, Direction::Right);
+                let _ = go(self, world, Direction::Right);
+
+                // Destroy down to get rocks
+                let _ = destroy(self, world, Direction::Down);
+
+                // Move down to get near rocks
+                let _ = go(self, world, Direction::Down);
+
+                // Start selling what you have
+
+                let mut rock_map:HashMap<Content, usize> = HashMap::new();
+                rock_map.insert(Content::Rock(0), 3);
+
+                assert_eq!(SwiftSeller::swift_seller(
+                    self,
+                    world,
+                    vec![Content::Rock(0), Content::Tree(0)]),
+                    Ok(rock_map)
+                );
+
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    /**************************************************************************
+    *  TWO MARKETS WORLD (SPAWN at 1,1):
+    *    ______________________________________
+    *   |            |            |            |
+    *   |    Grass   |   Grass    |   Grass    |
+    *   |    0 el    |   0 el     |   0 el     |
+    *   |    None    |  Tree(2)   |   None     |
+    *    ______________________________________
+    *   |            |            |            |
+    *   |    Grass   |   Grass    |   Grass    |
+    *   |    0 el    |   0 el     |   0 el     |
+    *   |  Market(2) |   None     | Market(0)  |
+    *    ______________________________________
+    *   |            |            |            |
+    *   |    Grass   |   Grass    |   Grass    |
+    *   |    0 el    |   0 el     |   0 el     |
+    *   |    None    |   None     |   None     |
+    *   |____________|____________|____________|
+    *
+    *   Copyright: comment format courtesy of the common crate
+    */
+
+    // Spawns at (1,1) genera
+    fn test_two_markets_world() -> impl Generator {
+        // World generator
+
+        struct TwoMarketsWorld { }
+        impl TwoMarketsWorld {
+            fn new() -> Self {
+                TwoMarketsWorld { }
+            }
+        }
+        impl Generator for TwoMarketsWorld {
+            fn gen(&mut self) -> robotics_lib::world::world_generator::World {
+                let mut map: Vec<Vec<Tile>> = Vec::new();
+
+                map.push(Vec::new());
+                map[0].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::None,
+                    elevation: 0,
+                });
+                map[0].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Tree(2),
+                    elevation: 0,
+                });
+                map[0].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::None,
+                    elevation: 0,
+                });
+
+                map.push(Vec::new());
+                map[1].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Market(2),
+                    elevation: 0,
+                });
+                map[1].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::None,
+                    elevation: 0,
+                });
+                map[1].push(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::Market(0),
+                    elevation: 0,
+                });
+
+                map.push(Vec::new());
+                for _ in 0..3 {
+                    map[2].push(Tile {
+                        tile_type: TileType::Grass,
+                        content: Content::None,
+                        elevation: 0,
+                    });
+                }
+
+                let environmental_conditions =
+                    EnvironmentalConditions::new(&[WeatherType::Sunny],
+                                                 15,
+                                                 12);
+                (map, (1, 1), environmental_conditions.unwrap(), 100.0, None)
+            }
+        }
+
+        TwoMarketsWorld::new()
+    }
+
+    #[test]
+    fn one_empty_market() {
+
+        // Generate the test world
+        let mut generator = test_two_markets_world();
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy the tree on top
+                match destroy(self, world, Direction::Up) {
+                    Ok(_) => (),
+                    Err(_) => eprintln!("Error when destroying")
+                }
+
+                match SwiftSeller::swift_seller(self, world, vec![Content::Tree(0)]) {
+                    Err(LibError::OperationNotAllowed) =>
+                        eprintln!("No Market nearby!"),
+                    Err(LibError::NotEnoughSpace(tried)) =>
+                        eprintln!("Can't hold {} coins!", tried),
+                    Err(any) =>
+                        eprintln!("{:?}", any),
+                    Ok(map) => {
+                        println!("Sold to market:");
+                        for (key, value) in map {
+                            println!("\t- item: {}, qty: {}", key, value)
+                        }
+                    }
+                }
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn nearest_known_market() {
+
+        // Generate the test world
+        let mut generator = test_two_markets_world();
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // The robot spawns between the two Markets, only the one on the left can be used
+                assert_eq!(SwiftSeller::nearest_market(self, world), Some(((1, 0), 0)));
+
+                // Walking away from it, the robot needs to come back one step
+                go(self, world, Direction::Down).expect("CANNOT MOVE");
+                assert_eq!(SwiftSeller::nearest_market(self, world), Some(((1, 0), 1)));
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn go_and_sell_to_market() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+
+                // The Market is on the diagonal, the robot has to take a step before selling
+                let result = SwiftSeller::go_and_sell(self, world, (1, 1), vec![Content::Tree(0)]);
+                assert!(result.expect("CANNOT SELL").contains_key(&Content::Tree(0)));
+
+                let position = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
+                assert!(navigation::is_adjacent(position, (1, 1)));
+
+                // There is no Market on the robot's starting tile
+                assert_eq!(
+                    SwiftSeller::go_and_sell(self, world, (0, 0), vec![Content::Tree(0)]),
+                    Err(LibError::OperationNotAllowed)
+                );
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn sale_plan_to_market() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+
+                let mut plan = SalePlan::to_market(self, world, (1, 1), vec![Content::Tree(0)], 100)
+                    .expect("CANNOT PLAN");
+                assert!(!plan.is_done());
+
+                // Plenty of energy, the whole plan fits in this tick
+                assert_eq!(plan.step(self, world), Ok(PlanStatus::Done));
+                assert!(plan.sold().contains_key(&Content::Tree(0)));
+
+                // Stepping a finished plan does nothing
+                assert_eq!(plan.step(self, world), Ok(PlanStatus::Done));
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    // A 5x5 grass world with a single Market out of sight, the robot spawns in a corner
+    fn test_far_market_world() -> impl Generator {
+        // World generator
+
+        struct FarMarketWorld { }
+        impl Generator for FarMarketWorld {
+            fn gen(&mut self) -> robotics_lib::world::world_generator::World {
+                let mut map: Vec<Vec<Tile>> = Vec::new();
+
+                for _ in 0..5 {
+                    let mut row = Vec::new();
+                    for _ in 0..5 {
+                        row.push(Tile {
+                            tile_type: TileType::Grass,
+                            content: Content::None,
+                            elevation: 0,
+                        });
+                    }
+                    map.push(row);
+                }
+                map[0][3].content = Content::Market(3);
+
+                let environmental_conditions =
+                    EnvironmentalConditions::new(&[WeatherType::Sunny],
+                                                 15,
+                                                 12);
+                (map, (0, 0), environmental_conditions.unwrap(), 100.0, None)
+            }
+        }
+
+        FarMarketWorld { }
+    }
+
+    #[test]
+    fn discover_far_market() {
+
+        // Generate the test world
+        let mut generator = test_far_market_world();
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // The Market is out of sight
+                assert_eq!(SwiftSeller::nearest_market(self, world), None);
+
+                let mut registry = MarketRegistry::new();
+                let discovery = explore_for_market(self, world, &mut registry, 200).expect("CANNOT EXPLORE");
+
+                assert_eq!(discovery.markets, vec![((0, 3), 3)]);
+                assert!(discovery.energy_spent <= 200);
+                assert_eq!(registry.usable(), vec![((0, 3), 3)]);
+                assert_eq!(SwiftSeller::nearest_market(self, world), Some(((0, 3), 2)));
+
+                // Now that a Market is known, exploring again costs nothing
+                let again = explore_for_market(self, world, &mut registry, 200).expect("CANNOT EXPLORE");
+                assert_eq!(again.energy_spent, 0);
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn auto_sell_next_to_market() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot, which knows nothing about selling
+
+        struct MyRobot(Robot, usize);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+                self.1 += 1;
+                match self.1 {
+                    // Destroy right to get trees, then stand next to the Market
+                    1 => {
+                        destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                        go(self, world, Direction::Right).expect("CANNOT MOVE");
+                    }
+                    // The trees were sold at the end of the previous tick
+                    _ => {
+                        let trees = self.get_backpack().get_contents().get(&Content::Tree(0)).copied();
+                        assert_eq!(trees.unwrap_or(0), 0);
+                    }
+                }
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = AutoSell::new(MyRobot(Robot::new(), 0), vec![Content::Tree(0)])
+            .with_trigger(SellTrigger::AdjacentToMarket);
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        let mut runner = run.expect("CANNOT START");
+        let _ = runner.game_tick();
+        let _ = runner.game_tick();
+    }
+
+    #[test]
+    fn seller_keeps_market_records() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot, SwiftSeller);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+
+                // The seller is taken out of the robot, since the robot is borrowed by the sale
+                let mut seller = std::mem::take(&mut self.1);
+                seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+
+                // One interaction has been used
+                assert_eq!(seller.markets().interactions((1, 1)), Some(9));
+                self.1 = seller;
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                self.1.handle_event(&event);
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new(), SwiftSeller::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn observers_follow_the_sale() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Observer, writing down every hook called
+
+        struct Recorder(Rc<RefCell<Vec<String>>>);
+
+        impl SaleObserver for Recorder {
+            fn on_market_selected(&mut self, coordinate: (usize, usize), _direction: &Direction, _interactions: usize, _reason: &SelectionReason) {
+                self.0.borrow_mut().push(format!("market {:?}", coordinate));
+            }
+            fn before_put(&mut self, item: &Content, _qty: usize) {
+                self.0.borrow_mut().push(format!("before {}", item));
+            }
+            fn after_put(&mut self, item: &Content, _qty: usize, result: &Result<usize, LibError>) {
+                self.0.borrow_mut().push(format!("after {} {}", item, result.is_ok()));
+            }
+            fn on_skip(&mut self, item: &Content, reason: &SkipReason) {
+                self.0.borrow_mut().push(format!("skip {} {:?}", item, reason));
+            }
+            fn on_complete(&mut self, report: &SaleReport) {
+                self.0.borrow_mut().push(format!("complete {}", report.items_sold.len()));
+            }
+        }
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+
+                let log = Rc::new(RefCell::new(Vec::new()));
+                let mut seller = SwiftSeller::new();
+                seller.add_observer(Box::new(Recorder(log.clone())));
+                seller.sell(self, world, vec![Content::Tree(0), Content::Fish(0), Content::Coin(0)]).expect("CANNOT SELL");
+
+                assert_eq!(*log.borrow(), vec![
+                    format!("market {:?}", (1, 1)),
+                    format!("before {}", Content::Tree(0)),
+                    format!("after {} true", Content::Tree(0)),
+                    format!("skip {} {:?}", Content::Fish(0), SkipReason::NotInBackpack),
+                    format!("skip {} {:?}", Content::Coin(0), SkipReason::NotSellable),
+                    "complete 1".to_string(),
+                ]);
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn trace_explains_the_sale() {
+
+        // Generate the test world
+        let mut generator = test_world(1);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Nothing to trade with: only the four neighbours are looked at
+                let (result, trace) = SwiftSeller::swift_seller_traced(self, world, vec![Content::Tree(0)]);
+                assert_eq!(result, Err(LibError::OperationNotAllowed));
+                assert_eq!(trace.stop_reason(), Some(&StopReason::NoMarketNearby));
+                assert_eq!(trace.entries.len(), 5);
+
+                // Same moves as `errore_alessandra`, the Market has a single interaction
+                let _ = destroy(self, world, Direction::Right);
+                let _ = go(self, world, Direction::Right);
+                let _ = go(self, world, Direction::Right);
+                let _ = destroy(self, world, Direction::Down);
+                let _ = go(self, world, Direction::Down);
+
+                let (result, trace) = SwiftSeller::swift_seller_traced(self, world, vec![Content::Rock(0), Content::Tree(0)]);
+                assert!(result.is_ok());
+                assert_eq!(trace.stop_reason(), Some(&StopReason::OutOfInteractions));
+                assert!(trace.entries.iter().any(|entry| matches!(
+                    entry,
+                    TraceEntry::MarketSelected { interactions: 1, reason: SelectionReason::OnlyCandidate, .. }
+                )));
+                assert!(trace.to_string().contains("Market"));
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn sell_until_coin_target() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+                let held = *self.get_backpack().get_contents().get(&Content::Tree(0)).unwrap();
+
+                // Trees are expected to be worth 2 coins, 2 of them are enough for 3 coins
+                let mut seller = SwiftSeller::new();
+                seller.prices_mut().set(&Content::Tree(0), 2.0);
+                seller.set_goal(SaleGoal::Coins(3));
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+
+                let sold = held.min(2);
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&sold));
+                assert_eq!(self.get_backpack().get_contents().get(&Content::Tree(0)), Some(&(held - sold)));
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn sell_until_free_slots() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+                let held = *self.get_backpack().get_contents().get(&Content::Tree(0)).unwrap();
+                let used: usize = self.get_backpack().get_contents().values().sum();
+                let free = self.get_backpack().get_size() - used;
+
+                // Trees worth 2 coins each would take more room than they free: nothing is sold, and the trace says why
+                let mut seller = SwiftSeller::new();
+                seller.set_goal(SaleGoal::FreeSlots(free + 1));
+                seller.set_tracing(true);
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert!(report.items_sold.is_empty());
+                assert_eq!(self.get_backpack().get_contents().get(&Content::Tree(0)), Some(&held));
+                assert!(seller.last_trace().unwrap().entries.iter().any(|entry| matches!(
+                    entry,
+                    TraceEntry::Skipped { reason: SkipReason::CannotFreeSpace, .. }
+                )));
+
+                // With room to spare the goal is reached before anything is sold
+                seller.set_goal(SaleGoal::FreeSlots(free));
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert!(report.items_sold.is_empty());
+                assert_eq!(seller.last_trace().unwrap().stop_reason(), Some(&StopReason::GoalReached));
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn sell_in_chunks() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+                let held = *self.get_backpack().get_contents().get(&Content::Tree(0)).unwrap();
+                let used: usize = self.get_backpack().get_contents().values().sum();
+                let free = self.get_backpack().get_size() - used;
+
+                // Trees believed worth as many coins as there are free slots: one at a time
+                let mut seller = SwiftSeller::new();
+                seller.set_chunking(Some(Chunking::default()));
+                seller.set_tracing(true);
+                seller.options_mut().verify = true;
+                seller.prices_mut().set(&Content::Tree(0), free as f64);
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&held));
+
+                let offers: Vec<usize> = seller.last_trace().unwrap().entries.iter()
+                    .filter_map(|entry| match entry {
+                        TraceEntry::Offered { qty, .. } => Some(*qty),
+                        _ => None
+                    })
+                    .collect();
+                assert_eq!(offers.first(), Some(&1));
+                assert_eq!(offers.iter().sum::<usize>(), held);
+                assert_eq!(report.interactions_used, offers.len());
+
+                // The robot is alone, the Market went down by one interaction per chunk
+                assert!(report.surprises.is_empty());
+                assert!(report.violations.is_empty());
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    // A 3x3 grass world with a Market on the left of the spawn, a Bank on its right, a Crate below
+    // and trees on top
+    fn test_bank_world() -> impl Generator {
+        // World generator
+
+        struct BankWorld { }
+        impl Generator for BankWorld {
+            fn gen(&mut self) -> robotics_lib::world::world_generator::World {
+                let mut map: Vec<Vec<Tile>> = Vec::new();
+
+                for _ in 0..3 {
+                    let mut row = Vec::new();
+                    for _ in 0..3 {
+                        row.push(Tile {
+                            tile_type: TileType::Grass,
+                            content: Content::None,
+                            elevation: 0,
+                        });
+                    }
+                    map.push(row);
+                }
+                map[0][1].content = Content::Tree(3);
+                map[1][0].content = Content::Market(10);
+                map[1][2].content = Content::Bank(0..100);
+                map[2][1].content = Content::Crate(0..10);
+
+                let environmental_conditions =
+                    EnvironmentalConditions::new(&[WeatherType::Sunny],
+                                                 15,
+                                                 12);
+                (map, (1, 1), environmental_conditions.unwrap(), 100.0, None)
+            }
+        }
+
+        BankWorld { }
+    }
+
+    #[test]
+    fn sell_and_bank_the_coins() {
+
+        // Generate the test world
+        let mut generator = test_bank_world();
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy the tree on top, the Market and the Bank are already next to the robot
+                destroy(self, world, Direction::Up).expect("CANNOT DESTROY");
+                let held = *self.get_backpack().get_contents().get(&Content::Tree(0)).unwrap();
+
+                let mut seller = SwiftSeller::new();
+                let report = seller.sell_and_bank(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&held));
+                assert_eq!(report.bank, Some((1, 2)));
+                assert!(report.coins_earned > 0);
+                assert_eq!(report.coins_banked + report.coins_kept, report.coins_earned);
+                assert_eq!(report.coins_kept, 0);
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
+
+        // Instance the robot and the world
+
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
+    }
+
+    #[test]
+    fn stash_the_leftovers() {
+
+        // Generate the test world
+        let mut generator = test_bank_world();
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy the tree on top, the Market and the Crate are already next to the robot
+                destroy(self, world, Direction::Up).expect("CANNOT DESTROY");
+                let held = *self.get_backpack().get_contents().get(&Content::Tree(0)).unwrap();
+
+                // A single tree is enough for a coin, the backpack has room for the rest
+                let mut seller = SwiftSeller::new();
+                seller.set_goal(SaleGoal::Coins(1));
+                seller.options_mut().stash_leftovers = true;
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&1));
+                assert!(report.stashed.is_empty());
+
+                // Trees believed worth more coins than the backpack holds: none is sold, the rest goes in the Crate
+                let used: usize = self.get_backpack().get_contents().values().sum();
+                let free = self.get_backpack().get_size() - used;
+                seller.set_goal(SaleGoal::Everything);
+                seller.set_chunking(Some(Chunking::default()));
+                seller.prices_mut().set(&Content::Tree(0), (free + 1) as f64);
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert!(report.items_sold.is_empty());
+                assert_eq!(report.stashed.get(&Content::Tree(0)), Some(&(held - 1)));
+                assert_eq!(seller.stash().stored_at((2, 1)).and_then(|items| items.get(&Content::Tree(0))), Some(&(held - 1)));
+                assert_eq!(self.get_backpack().get_contents().get(&Content::Tree(0)).copied().unwrap_or(0), 0);
+            }
+
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
+
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
 
-#[cfg(test)]
-mod tests {
-    use std::process::exit;
+        // Instance the robot and the world
 
-    use robotics_lib::energy::Energy;
-    use robotics_lib::event::events::Event;
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
 
-    use robotics_lib::interface::{Direction, go, where_am_i, destroy };
+        start(run);
+    }
 
-    use robotics_lib::runner::backpack::BackPack;
-    use robotics_lib::runner::{Robot, Runner};
+    #[test]
+    fn harvest_around_the_market() {
 
-    use robotics_lib::world::coordinates::Coordinate;
-    use robotics_lib::world::environmental_conditions::{EnvironmentalConditions, WeatherType};
-    use robotics_lib::world::tile::{Tile, TileType};
-    use robotics_lib::world::world_generator::Generator;
+        // Generate the test world
+        let mut generator = test_world(10);
 
-    use super::*;
+        // Robot
 
-    /**************************************************************************
-    *  MAP:
-    *    ______________________________________
-    *   |            |            |            |
-    *   |    Grass   |   Grass    |   Grass    |
-    *   |    0 el    |   0 el     |    0 el    |
-    *   |    None    |  Tree(3)   |   Tree(4)  |
-    *   |____________|____________|____________|
-    *   |            |            |            |
-    *   |   Shallow  |   Grass    |   Grass    |
-    *   |    0 el    |   0 el     |    0 el    |
-    *   |   Fish(3)  | Market(1)  |   Rock(3)  |
-    *   |____________|____________|____________|
-    *   |            |            |            |
-    *   |    Grass   |    Grass   |   Grass    |
-    *   |    0 el    |    0 el    |    0 el    |
-    *   |   Rock(2)  |    None    |   Rock(6)  |
-    *   |____________|____________|____________|
-    *
-    *   Copyright: comment format courtesy of the common crate
-    */
+        struct MyRobot(Robot);
 
-    fn test_world(market_interactions: usize) -> impl Generator {
-        // World generator
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
 
-        struct MarketWorld {
-            market_interactions: usize
-        }
-        impl MarketWorld {
-            fn new(market_interactions: usize) -> Self {
-                MarketWorld {
-                    market_interactions
+                // Every tile around the Market holds a resource
+                let mut seller = SwiftSeller::new();
+                let report = harvest_and_sell(&mut seller, self, world, (1, 1), 2, 300).expect("CANNOT HARVEST");
+                assert!(!report.harvested.is_empty());
+                assert!(!report.sales.is_empty());
+                assert!(report.coins_earned > 0);
+                assert!(report.energy_spent <= 300);
+                for (item, qty) in &report.items_sold {
+                    assert!(report.harvested.get(item).is_some_and(|harvested| qty <= harvested));
                 }
-            }
-        }
-        impl Generator for MarketWorld {
-            fn gen(&mut self) -> robotics_lib::world::world_generator::World {
-                let mut map: Vec<Vec<Tile>> = Vec::new();
-
-                map.push(Vec::new());
-                map[0].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::None,
-                    elevation: 0,
-                });
-                map[0].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Tree(3),
-                    elevation: 0,
-                });
-                map[0].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Tree(4),
-                    elevation: 0,
-                });
 
-                map.push(Vec::new());
-                map[1].push(Tile {
-                    tile_type: TileType::ShallowWater,
-                    content: Content::Fish(3),
-                    elevation: 0,
-                });
-                map[1].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Market(self.market_interactions),
-                    elevation: 0,
-                });
-                map[1].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Rock(3),
-                    elevation: 0,
-                });
+                // There is no Market on the robot's tile
+                let position = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
+                assert_eq!(
+                    harvest_and_sell(&mut seller, self, world, position, 2, 300),
+                    Err(LibError::OperationNotAllowed)
+                );
+            }
 
-                map.push(Vec::new());
-                map[2].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Rock(2),
-                    elevation: 0,
-                });
-                map[2].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::None,
-                    elevation: 0,
-                });
-                map[2].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Rock(6),
-                    elevation: 0,
-                });
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
 
-                let environmental_conditions =
-                    EnvironmentalConditions::new(&[WeatherType::Sunny],
-                                                 15,
-                                                 12);
-                (map, (0, 0), environmental_conditions.unwrap(), 100.0, None)
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
             }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
         }
 
-        MarketWorld::new(market_interactions)
-    }
+        // Instance the robot and the world
 
-    fn start(run: Result<Runner, LibError>) {
-        match run {
-            | Ok(mut r) => {
-                let _ = r.game_tick();
-            }
-            | Err(e) => {
-                println!("{:?}", e);
-                exit(1)
-            },
-        }
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
+
+        start(run);
     }
 
     #[test]
-    fn no_market_nearby() {
+    fn deny_a_market() {
 
         // Generate the test world
-        let mut generator = test_world(0);
+        let mut generator = test_two_markets_world();
 
         // Robot
 
@@ -291,26 +2776,32 @@ mod tests {
         impl Runnable for MyRobot {
             fn process_tick(&mut self, world: &mut World) {
 
-                // List all the movements I intend to make and the outcomes of the function call
-                // after the corresponding movement
-                let movements:&[Direction] = &[
-                    Direction::Right, Direction::Right,
-                    Direction::Down, Direction::Down,
-                    Direction::Left, Direction::Left,
-                    Direction::Up, Direction::Up
-                ];
+                // Destroy the tree on top
+                destroy(self, world, Direction::Up).expect("CANNOT DESTROY");
+
+                // The only Market with interactions left is reserved for someone else
+                let filter = MarketFilter::new().with_denied((1, 0));
+                assert_eq!(SwiftSeller::nearest_allowed_market(self, world, &filter), None);
+                assert_eq!(SwiftSeller::nearest_market(self, world), Some(((1, 0), 0)));
+
+                let mut seller = SwiftSeller::new();
+                seller.options_mut().markets = filter.clone();
+                assert_eq!(seller.sell(self, world, vec![Content::Tree(0)]), Err(LibError::OperationNotAllowed));
+                assert_eq!(seller.walk_and_sell(self, world, (1, 0), vec![Content::Tree(0)]), Err(LibError::OperationNotAllowed));
+                assert_eq!(
+                    SalePlan::to_allowed_market(self, world, (1, 0), vec![Content::Tree(0)], 100, &filter).err(),
+                    Some(LibError::OperationNotAllowed)
+                );
 
-                // For each movement, perform the following actions
-                for movement in movements {
-                    // Since I created a world ad hoc, those movements should be possible
-                    go(self, world, movement.clone()).expect("CANNOT MOVE");
+                // A plan made before the Market was denied doesn't sell there either
+                let mut plan = SalePlan::new(vec![SaleStep::Sell { market: (1, 0), items: vec![(Content::Tree(0), 1)] }])
+                    .with_markets(filter);
+                assert_eq!(plan.step(self, world), Err(LibError::OperationNotAllowed));
+                assert!(plan.sold().is_empty());
 
-                    // Every move I should NOT find a market nearby (or find one with value 0)
-                    assert_eq!(
-                        SwiftSeller::swift_seller(self, world, vec![Content::Fish(0), Content::Tree(0), Content::Rock(0)]),
-                        Err(LibError::OperationNotAllowed)
-                    )
-                }
+                seller.options_mut().markets = MarketFilter::new().with_preferred((1, 2));
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.market, Some((1, 0)));
             }
 
             fn handle_event(&mut self, event: Event) {
@@ -343,13 +2834,11 @@ mod tests {
         let my_robot = MyRobot(Robot::new());
         let run = Runner::new(Box::new(my_robot), &mut generator);
 
-        // Since the weather is sunny day, the robot is walking on grass, and it starts with full
-        // energy, I can walk around the Market content all in one tick
         start(run);
     }
 
     #[test]
-    fn sell_empty_backpack() {
+    fn postpone_the_plan() {
 
         // Generate the test world
         let mut generator = test_world(10);
@@ -361,34 +2850,94 @@ mod tests {
         impl Runnable for MyRobot {
             fn process_tick(&mut self, world: &mut World) {
 
-                // List all the movements I intend to make and the outcomes of the function call
-                // after the corresponding movement
-                let movements:&[Direction] = &[
-                    Direction::Right, Direction::Right,
-                    Direction::Down, Direction::Down,
-                    Direction::Left, Direction::Left,
-                    Direction::Up, Direction::Up
-                ];
+                // Destroy right to get trees
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+
+                // The test world is always sunny
+                let mut plan = SalePlan::to_market(self, world, (1, 1), vec![Content::Tree(0)], 100)
+                    .expect("CANNOT PLAN")
+                    .with_window(SaleWindow::new().no_travel_in(WeatherType::Sunny));
+                assert_eq!(plan.step(self, world), Ok(PlanStatus::Postponed));
+                assert_eq!((self.get_coordinate().get_row(), self.get_coordinate().get_col()), (0, 0));
+
+                let mut plan = plan.with_window(SaleWindow::new().no_sale_in(WeatherType::Sunny));
+                assert_eq!(plan.step(self, world), Ok(PlanStatus::Postponed));
+                assert_eq!(plan.remaining().len(), 1);
+
+                // The seller refuses to sell too
+                let mut seller = SwiftSeller::new();
+                seller.options_mut().window = SaleWindow::new().no_sale_in(WeatherType::Sunny);
+                assert_eq!(seller.sell(self, world, vec![Content::Tree(0)]), Err(LibError::OperationNotAllowed));
+
+                let mut plan = plan.with_window(SaleWindow::new());
+                assert_eq!(plan.step(self, world), Ok(PlanStatus::Done));
+            }
 
-                // For each movement, perform the following actions
-                for (i, movement) in movements.iter().enumerate() {
-                    // Since I created a world ad hoc, those movements should be possible
-                    go(self, world, movement.clone()).expect("CANNOT MOVE");
+            fn handle_event(&mut self, event: Event) {
+                match event {
+                    | Event::Terminated => {}
+                    | _ => {}
+                }
+            }
 
-                    // When I'm nearby a market, I should try to sell everything; I have an empty
-                    // backpack, so the returned value from the function call should be:
-                    let empty_map:HashMap<Content, usize> = HashMap::new();
+            fn get_energy(&self) -> &Energy {
+                &self.0.energy
+            }
+            fn get_energy_mut(&mut self) -> &mut Energy {
+                &mut self.0.energy
+            }
+            fn get_coordinate(&self) -> &Coordinate {
+                &self.0.coordinate
+            }
+            fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+                &mut self.0.coordinate
+            }
+            fn get_backpack(&self) -> &BackPack {
+                &self.0.backpack
+            }
+            fn get_backpack_mut(&mut self) -> &mut BackPack { &mut self.0.backpack }
+        }
 
-                    // Let's check
-                    if i % 2 == 0 {
+        // Instance the robot and the world
 
-                        assert_eq!(
+        let my_robot = MyRobot(Robot::new());
+        let run = Runner::new(Box::new(my_robot), &mut generator);
 
-                            SwiftSeller::swift_seller(self, world, vec![Content::Fish(0), Content::Tree(0), Content::Rock(0)]),
-                            Ok(empty_map)
-                        )
-                    }
-                }
+        start(run);
+    }
+
+    #[test]
+    fn daily_quota() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
+
+        // Robot
+
+        struct MyRobot(Robot);
+
+        impl Runnable for MyRobot {
+            fn process_tick(&mut self, world: &mut World) {
+
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+
+                // A single tree a day
+                let mut seller = SwiftSeller::new();
+                seller.set_quota(DailyQuota::new().with_item_limit(Content::Tree(0), 1));
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&1));
+
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert!(report.items_sold.is_empty());
+
+                // A new day, another tree
+                let conditions = EnvironmentalConditions::new(&[WeatherType::Sunny], 15, 12).unwrap();
+                seller.handle_event(&Event::DayChanged(conditions));
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&1));
+                assert_eq!(seller.quota().sold_today().get(&Content::Tree(0)), Some(&1));
             }
 
             fn handle_event(&mut self, event: Event) {
@@ -421,16 +2970,14 @@ mod tests {
         let my_robot = MyRobot(Robot::new());
         let run = Runner::new(Box::new(my_robot), &mut generator);
 
-        // Since the weather is sunny day, the robot is walking on grass, and it starts with full
-        // energy, I can walk around the Market content all in one tick
         start(run);
     }
 
     #[test]
-    fn destroy_and_sell_items() {
+    fn plan_sells_at_the_planned_market() {
 
         // Generate the test world
-        let mut generator = test_world(10);
+        let mut generator = test_two_markets_world();
 
         // Robot
 
@@ -439,72 +2986,17 @@ mod tests {
         impl Runnable for MyRobot {
             fn process_tick(&mut self, world: &mut World) {
 
-                // List all the movements I intend to make and the outcomes of the function call
-                // after the corresponding movement
-                let movements:&[Direction] = &[
-                    Direction::Right, Direction::Right,
-                    Direction::Down, Direction::Down,
-                    Direction::Left, Direction::Left,
-                    Direction::Up, Direction::Up
-                ];
-
-                // For each movement, perform the following actions
-                for  movement in movements {
-
-                    println!("--------");
-
-                    println!("Destroy {:?}", movement);
-                    // Since I created a world ad hoc, these destroy() should have enough energy
-                    match destroy(self, world, movement.clone()) {
-                        Ok(_) => (),
-                        Err(error) =>
-                            eprintln!("ERR\n\tERROR: {:?}\n\tMOVEMENT: {:?}\n\tBACKPACK SIZE: {}",
-                                      error,
-                                      movement,
-                                      self.get_backpack().get_size()
-                            )
-                    }
-
-                    // Since I created a world ad hoc, those movements should be possible
-                    println!("Go {:?}", movement);
-                    go(self, world, movement.clone()).expect("CANNOT MOVE");
-
-                    let (robot_view, _) = where_am_i(self, &world);
+                // Destroy the tree on top
+                destroy(self, world, Direction::Up).expect("CANNOT DESTROY");
 
-                    for row in robot_view.iter() {
-                        for col in row.iter() {
-                            match col {
-                                | None => print!(" None "),
-                                | Some(tile) => {
-                                    if tile.content != Content::None {
-                                        print!(" {:?}({:?}) ", tile.tile_type, tile.content)
-                                    }
-                                    else {
-                                        print!(" {:?} ", tile.tile_type)
-                                    }
-                                },
-                            }
-                        }
-                        println!();
-                    }
+                // The planned Market has no interactions left, the one on the left is not part of the plan
+                let mut plan = SalePlan::new(vec![SaleStep::Sell { market: (1, 2), items: vec![(Content::Tree(0), 1)] }]);
+                assert_eq!(plan.step(self, world), Err(LibError::OperationNotAllowed));
+                assert!(plan.sold().is_empty());
 
-                    // Now I can finally call the function to interact with the Market
-                    println!("Sell?");
-                    match SwiftSeller::swift_seller(self, world, vec![Content::Fish(0), Content::Tree(0), Content::Rock(0)]) {
-                        Err(LibError::OperationNotAllowed) =>
-                            eprintln!("No Market nearby!"),
-                        Err(LibError::NotEnoughSpace(tried)) =>
-                            eprintln!("Can't hold {} coins!", tried),
-                        Err(any) =>
-                            eprintln!("{:?}", any),
-                        Ok(map) => {
-                            println!("Sold to market:");
-                            for (key, value) in map {
-                                println!("\t- item: {}, qty: {}", key, value)
-                            }
-                        }
-                    }
-                }
+                let mut plan = SalePlan::new(vec![SaleStep::Sell { market: (1, 0), items: vec![(Content::Tree(0), 1)] }]);
+                assert_eq!(plan.step(self, world), Ok(PlanStatus::Done));
+                assert!(plan.sold().contains_key(&Content::Tree(0)));
             }
 
             fn handle_event(&mut self, event: Event) {
@@ -537,15 +3029,14 @@ mod tests {
         let my_robot = MyRobot(Robot::new());
         let run = Runner::new(Box::new(my_robot), &mut generator);
 
-        // Since the weather is sunny day, the robot is walking on grass, and it starts with full
-        // energy, I can walk around the Market content all in one tick
         start(run);
     }
 
     #[test]
-    fn zero_energy() {
-    // Generate the test world
-        let mut generator = test_world(100);
+    fn sell_what_the_broker_reserved() {
+
+        // Generate the test world
+        let mut generator = test_world(10);
 
         // Robot
 
@@ -553,34 +3044,39 @@ mod tests {
 
         impl Runnable for MyRobot {
             fn process_tick(&mut self, world: &mut World) {
-                println!("{:?}", self.get_energy());
-                match destroy(self, world, Direction::Right) {
-                    Ok(_) => (),
-                    Err(error) =>
-                        eprintln!("ERR\n\tERROR: {:?}\n\tMOVEMENT: {:?}\n\tBACKPACK SIZE: {}",
-                                  error,
-                                  Direction::Right,
-                                  self.get_backpack().get_size()
-                        )
-                }
-                println!("{:?}", self.get_energy());
-                go(self, world, Direction::Right).expect("CANNOT MOVE");
-                println!("{:?}", self.get_energy());
-                for _ in 0..498 {
-                    go(self, world, Direction::Right).expect("CANNOT MOVE");
-                    go(self, world, Direction::Left).expect("CANNOT MOVE");
-                }
-                println!("{:?}", self.get_energy());
-                println!("{:?}", self.get_backpack());
 
-                match SwiftSeller::swift_seller(self, world, vec![Content::Tree(0)]) {
-                    Ok(_) => {}
-                    Err(_) => {}
+                // Destroy right to get trees, then stand next to the Market
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                go(self, world, Direction::Right).expect("CANNOT MOVE");
+                let held = *self.get_backpack().get_contents().get(&Content::Tree(0)).unwrap();
+                let used: usize = self.get_backpack().get_contents().values().sum();
+                let free = self.get_backpack().get_size() - used;
+
+                let broker = MarketBroker::new();
+                let robot = broker.register();
+                broker.update((1, 1), 10);
+
+                // Trees sold one at a time, nothing reserved yet
+                let mut seller = SwiftSeller::new();
+                seller.set_broker(broker.clone(), robot);
+                seller.set_chunking(Some(Chunking::default()));
+                seller.prices_mut().set(&Content::Tree(0), free as f64);
+                assert_eq!(seller.sell(self, world, vec![Content::Tree(0)]), Err(LibError::OperationNotAllowed));
+
+                // Two interactions reserved, two trees sold
+                assert_eq!(broker.reserve(robot, (1, 1), 2, std::time::Duration::from_secs(60)), 2);
+                seller.set_tracing(true);
+                let report = seller.sell(self, world, vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert_eq!(report.interactions_used, held.min(2));
+                assert_eq!(report.items_sold.get(&Content::Tree(0)), Some(&held.min(2)));
+                assert_eq!(broker.reserved_by(robot, (1, 1)), 2 - held.min(2));
+
+                // The Market has interactions left, the reservation ran out
+                if held > 2 {
+                    let stop = seller.last_trace().and_then(|trace| trace.stop_reason());
+                    assert_eq!(stop, Some(&StopReason::ReservationExhausted));
                 }
-                println!("{:?}", self.get_energy());
-                println!("{:?}", self.get_backpack());
             }
-
             fn handle_event(&mut self, event: Event) {
                 match event {
                     | Event::Terminated => {}
@@ -614,12 +3110,11 @@ mod tests {
         start(run);
     }
 
-    // After selling some of the items, if the market gets to zero interactions the tool returns
-    // an error, while it should return an empty hashmap/the map of items sold up to that point
     #[test]
-    fn errore_alessandra() {
+    fn walk_only_in_good_weather() {
+
         // Generate the test world
-        let mut generator = test_world(1);
+        let mut generator = test_world(10);
 
         // Robot
 
@@ -627,33 +3122,34 @@ mod tests {
 
         impl Runnable for MyRobot {
             fn process_tick(&mut self, world: &mut World) {
-                // Destroy right to get trees
-                let _ = destroy(self, world, Direction::Right);
-
-                // Move right-right to get near rocks
-                let _ = go(self, world, Direction::Right);
-                let _ = go(self, world, Direction::Right);
-
-                // Destroy down to get rocks
-                let _ = destroy(self, world, Direction::Down);
-
-                // Move down to get near rocks
-                let _ = go(self, world, Direction::Down);
-
-                // Start selling what you have
-
-                let mut rock_map:HashMap<Content, usize> = HashMap::new();
-                rock_map.insert(Content::Rock(0), 3);
 
-                assert_eq!(SwiftSeller::swift_seller(
-                    self,
-                    world,
-                    vec![Content::Rock(0), Content::Tree(0)]),
-                    Ok(rock_map)
+                // Destroy right to get trees
+                destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+
+                // No walking in the sun: the robot stays where it is
+                let mut seller = SwiftSeller::new();
+                seller.options_mut().window = SaleWindow::new().no_travel_in(WeatherType::Sunny);
+                assert_eq!(
+                    seller.walk_and_sell(self, world, (1, 1), vec![Content::Tree(0)]),
+                    Err(LibError::OperationNotAllowed)
+                );
+                assert_eq!((self.get_coordinate().get_row(), self.get_coordinate().get_col()), (0, 0));
+
+                // No selling in the sun: the robot doesn't spend any energy on the trip
+                let energy = self.get_energy().get_energy_level();
+                seller.options_mut().window = SaleWindow::new().no_sale_in(WeatherType::Sunny);
+                assert_eq!(
+                    seller.walk_and_sell(self, world, (1, 1), vec![Content::Tree(0)]),
+                    Err(LibError::OperationNotAllowed)
                 );
+                assert_eq!((self.get_coordinate().get_row(), self.get_coordinate().get_col()), (0, 0));
+                assert_eq!(self.get_energy().get_energy_level(), energy);
 
+                // Only rain keeps the robot in
+                seller.options_mut().window = SaleWindow::new().no_travel_in(WeatherType::Rainy);
+                let report = seller.walk_and_sell(self, world, (1, 1), vec![Content::Tree(0)]).expect("CANNOT SELL");
+                assert!(report.items_sold.contains_key(&Content::Tree(0)));
             }
-
             fn handle_event(&mut self, event: Event) {
                 match event {
                     | Event::Terminated => {}
@@ -687,127 +3183,32 @@ mod tests {
         start(run);
     }
 
-    /**************************************************************************
-    *  TWO MARKETS WORLD (SPAWN at 1,1):
-    *    ______________________________________
-    *   |            |            |            |
-    *   |    Grass   |   Grass    |   Grass    |
-    *   |    0 el    |   0 el     |   0 el     |
-    *   |    None    |  Tree(2)   |   None     |
-    *    ______________________________________
-    *   |            |            |            |
-    *   |    Grass   |   Grass    |   Grass    |
-    *   |    0 el    |   0 el     |   0 el     |
-    *   |  Market(2) |   None     | Market(0)  |
-    *    ______________________________________
-    *   |            |            |            |
-    *   |    Grass   |   Grass    |   Grass    |
-    *   |    0 el    |   0 el     |   0 el     |
-    *   |    None    |   None     |   None     |
-    *   |____________|____________|____________|
-    *
-    *   Copyright: comment format courtesy of the common crate
-    */
-
-    // Spawns at (1,1) genera
-    fn test_two_markets_world() -> impl Generator {
-        // World generator
-
-        struct TwoMarketsWorld { }
-        impl TwoMarketsWorld {
-            fn new() -> Self {
-                TwoMarketsWorld { }
-            }
-        }
-        impl Generator for TwoMarketsWorld {
-            fn gen(&mut self) -> robotics_lib::world::world_generator::World {
-                let mut map: Vec<Vec<Tile>> = Vec::new();
-
-                map.push(Vec::new());
-                map[0].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::None,
-                    elevation: 0,
-                });
-                map[0].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Tree(2),
-                    elevation: 0,
-                });
-                map[0].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::None,
-                    elevation: 0,
-                });
-
-                map.push(Vec::new());
-                map[1].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Market(2),
-                    elevation: 0,
-                });
-                map[1].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::None,
-                    elevation: 0,
-                });
-                map[1].push(Tile {
-                    tile_type: TileType::Grass,
-                    content: Content::Market(0),
-                    elevation: 0,
-                });
-
-                map.push(Vec::new());
-                for _ in 0..3 {
-                    map[2].push(Tile {
-                        tile_type: TileType::Grass,
-                        content: Content::None,
-                        elevation: 0,
-                    });
-                }
-
-                let environmental_conditions =
-                    EnvironmentalConditions::new(&[WeatherType::Sunny],
-                                                 15,
-                                                 12);
-                (map, (1, 1), environmental_conditions.unwrap(), 100.0, None)
-            }
-        }
-
-        TwoMarketsWorld::new()
-    }
-
     #[test]
-    fn one_empty_market() {
+    fn engine_keeps_firing_until_sold() {
 
         // Generate the test world
-        let mut generator = test_two_markets_world();
+        let mut generator = test_world(10);
 
-        // Robot
+        // Robot, which knows nothing about selling
 
-        struct MyRobot(Robot);
+        struct MyRobot(Robot, usize);
 
         impl Runnable for MyRobot {
             fn process_tick(&mut self, world: &mut World) {
-
-                // Destroy the tree on top
-                match destroy(self, world, Direction::Up) {
-                    Ok(_) => (),
-                    Err(_) => eprintln!("Error when destroying")
-                }
-
-                match SwiftSeller::swift_seller(self, world, vec![Content::Tree(0)]) {
-                    Err(LibError::OperationNotAllowed) =>
-                        eprintln!("No Market nearby!"),
-                    Err(LibError::NotEnoughSpace(tried)) =>
-                        eprintln!("Can't hold {} coins!", tried),
-                    Err(any) =>
-                        eprintln!("{:?}", any),
-                    Ok(map) => {
-                        println!("Sold to market:");
-                        for (key, value) in map {
-                            println!("\t- item: {}, qty: {}", key, value)
-                        }
+                self.1 += 1;
+                match self.1 {
+                    // Destroy right to get trees, the Market is not next to the robot: the sale fails
+                    1 => {
+                        destroy(self, world, Direction::Right).expect("CANNOT DESTROY");
+                    }
+                    // Stand next to the Market, the trees gained before still count
+                    2 => {
+                        go(self, world, Direction::Right).expect("CANNOT MOVE");
+                    }
+                    // The trees were sold at the end of the previous tick
+                    _ => {
+                        let trees = self.get_backpack().get_contents().get(&Content::Tree(0)).copied();
+                        assert_eq!(trees.unwrap_or(0), 0);
                     }
                 }
             }
@@ -839,9 +3240,14 @@ mod tests {
 
         // Instance the robot and the world
 
-        let my_robot = MyRobot(Robot::new());
+        let engine = TriggerEngine::new(vec![TriggerCondition::GainedSinceLastSale(Content::Tree(0), 1)]);
+        let my_robot = AutoSell::new(MyRobot(Robot::new(), 0), vec![Content::Tree(0)])
+            .with_engine(engine);
         let run = Runner::new(Box::new(my_robot), &mut generator);
 
-        start(run);
+        let mut runner = run.expect("CANNOT START");
+        for _ in 0..3 {
+            let _ = runner.game_tick();
+        }
     }
 }
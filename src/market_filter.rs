@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+/// Which Markets, by coordinate, a seller may use and which it should pick first
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::market_filter::MarketFilter;
+/// let filter = MarketFilter::new().with_denied((4, 2)).with_preferred((0, 3));
+/// ```
+///
+/// # Notes
+/// - with no allowed coordinate every Market is allowed, otherwise only the allowed ones
+/// - a denied Market is never used, even if allowed or preferred
+/// - a preferred Market is picked over the others, however many interactions or steps away it is
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketFilter {
+    allowed: Option<HashSet<(usize, usize)>>,
+    denied: HashSet<(usize, usize)>,
+    preferred: HashSet<(usize, usize)>,
+}
+
+impl MarketFilter {
+    pub fn new() -> Self {
+        MarketFilter::default()
+    }
+
+    /// Allows the Market at `market`, the Markets not allowed can't be used anymore
+    pub fn with_allowed(mut self, market: (usize, usize)) -> Self {
+        self.allowed.get_or_insert_with(HashSet::new).insert(market);
+        self
+    }
+
+    pub fn with_denied(mut self, market: (usize, usize)) -> Self {
+        self.denied.insert(market);
+        self
+    }
+
+    pub fn with_preferred(mut self, market: (usize, usize)) -> Self {
+        self.preferred.insert(market);
+        self
+    }
+
+    /// This filter, allowing no other Market than `market`
+    pub(crate) fn restricted_to(&self, market: (usize, usize)) -> MarketFilter {
+        let allowed = if self.allows(market) { HashSet::from([market]) } else { HashSet::new() };
+        MarketFilter {
+            allowed: Some(allowed),
+            ..self.clone()
+        }
+    }
+
+    /// Whether the Market at `market` may be used
+    pub fn allows(&self, market: (usize, usize)) -> bool {
+        !self.denied.contains(&market) && self.allowed.as_ref().is_none_or(|allowed| allowed.contains(&market))
+    }
+
+    /// Whether the Market at `market` may be used and should be picked first
+    pub fn prefers(&self, market: (usize, usize)) -> bool {
+        self.allows(market) && self.preferred.contains(&market)
+    }
+
+    /// The Markets of `markets` that may be used, as `(coordinate, interactions)`
+    pub fn apply(&self, markets: Vec<((usize, usize), usize)>) -> Vec<((usize, usize), usize)> {
+        markets.into_iter().filter(|(market, _)| self.allows(*market)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deny_wins() {
+        let filter = MarketFilter::new()
+            .with_allowed((0, 0))
+            .with_allowed((1, 1))
+            .with_denied((1, 1))
+            .with_preferred((1, 1));
+
+        assert!(filter.allows((0, 0)));
+        assert!(!filter.allows((1, 1)));
+        assert!(!filter.prefers((1, 1)));
+        assert!(!filter.allows((2, 2)));
+        assert_eq!(filter.apply(vec![((0, 0), 1), ((1, 1), 5), ((2, 2), 3)]), vec![((0, 0), 1)]);
+
+        // Without an allow list, everything not denied is allowed
+        assert!(MarketFilter::new().with_denied((1, 1)).allows((2, 2)));
+    }
+
+    #[test]
+    fn restrict_to_one_market() {
+        let filter = MarketFilter::new().with_denied((1, 1));
+        assert!(filter.restricted_to((0, 0)).allows((0, 0)));
+        assert!(!filter.restricted_to((0, 0)).allows((2, 2)));
+
+        // A denied Market stays denied
+        assert!(!filter.restricted_to((1, 1)).allows((1, 1)));
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, VecDeque};
+use robotics_lib::interface::Direction;
+use robotics_lib::world::tile::{Content, Tile};
+
+/// The robot's known map, as returned by `robot_map`
+pub type KnownMap = Vec<Vec<Option<Tile>>>;
+
+/// Returns the coordinate reached by moving one tile in `direction`, if it is inside the map
+pub(crate) fn step(map: &[Vec<Option<Tile>>], from: (usize, usize), direction: &Direction) -> Option<(usize, usize)> {
+    let (row, col) = from;
+    let next = match direction {
+        Direction::Up => (row.checked_sub(1)?, col),
+        Direction::Down => (row + 1, col),
+        Direction::Left => (row, col.checked_sub(1)?),
+        Direction::Right => (row, col + 1),
+    };
+    if next.0 < map.len() && next.1 < map[next.0].len() {
+        Some(next)
+    } else {
+        None
+    }
+}
+
+/// Returns the coordinate reached by following `path` from `from`
+pub fn follow(map: &[Vec<Option<Tile>>], from: (usize, usize), path: &[Direction]) -> Option<(usize, usize)> {
+    path.iter().try_fold(from, |position, direction| step(map, position, direction))
+}
+
+/// Whether two coordinates are next to each other (no diagonals)
+pub fn is_adjacent(a: (usize, usize), b: (usize, usize)) -> bool {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1) == 1
+}
+
+/// Whether the robot can walk on a known tile (deep water, lava, walls... are excluded)
+pub fn is_walkable(tile: &Tile) -> bool {
+    tile.tile_type.properties().walk()
+}
+
+/// Rough energy cost of walking onto `to` from `from`: the tile's base cost plus the climb
+pub fn step_energy(from: &Tile, to: &Tile) -> usize {
+    let climb = to.elevation.saturating_sub(from.elevation);
+    to.tile_type.properties().cost() + climb.pow(2)
+}
+
+/// Interactions left at the known `Market` on `coordinate`, 0 if there is no known Market there
+pub fn market_at(map: &[Vec<Option<Tile>>], coordinate: (usize, usize)) -> usize {
+    match map.get(coordinate.0).and_then(|row| row.get(coordinate.1)) {
+        Some(Some(Tile { content: Content::Market(n), .. })) => *n,
+        _ => 0
+    }
+}
+
+/// Lists every known `Market` with at least one interaction left, as `(coordinate, interactions)`
+pub fn known_markets(map: &[Vec<Option<Tile>>]) -> Vec<((usize, usize), usize)> {
+    let mut markets = Vec::new();
+    for (i, row) in map.iter().enumerate() {
+        for (j, col) in row.iter().enumerate() {
+            if let Some(Tile { content: Content::Market(n), .. }) = col {
+                if *n > 0 {
+                    markets.push(((i, j), *n));
+                }
+            }
+        }
+    }
+    markets
+}
+
+/// Breadth-first search over the known walkable tiles
+///
+/// # Arguments
+/// - `map`: The robot's known map
+/// - `start`: Where the robot stands
+/// - `is_goal`: Tells whether a coordinate is a valid destination
+///
+/// # Returns
+/// - `Some((destination, path))`: The closest destination and the directions to reach it
+/// - `None`: No destination can be reached through known walkable tiles
+///
+/// # Notes
+/// - unknown tiles are never walked on, the planner only trusts what the robot has seen
+pub fn shortest_path(
+    map: &[Vec<Option<Tile>>],
+    start: (usize, usize),
+    is_goal: impl Fn((usize, usize)) -> bool
+) -> Option<((usize, usize), Vec<Direction>)> {
+
+    let mut parents: HashMap<(usize, usize), ((usize, usize), Direction)> = HashMap::new();
+    let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(current) {
+            // Walk back to the start to rebuild the path
+            let mut path = Vec::new();
+            let mut position = current;
+            while position != start {
+                let (parent, direction) = parents[&position].clone();
+                path.push(direction);
+                position = parent;
+            }
+            path.reverse();
+            return Some((current, path));
+        }
+
+        for direction in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+            if let Some(next) = step(map, current, &direction) {
+                if next == start || parents.contains_key(&next) {
+                    continue;
+                }
+                match &map[next.0][next.1] {
+                    Some(tile) if is_walkable(tile) => {
+                        parents.insert(next, (current, direction));
+                        queue.push_back(next);
+                    }
+                    _ => ()
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use robotics_lib::world::tile::TileType;
+
+    use super::*;
+
+    /// Builds a fully known map out of tile types, all tiles empty and flat
+    pub(crate) fn flat_map(types: &[&[TileType]]) -> KnownMap {
+        types.iter()
+            .map(|row| row.iter()
+                .map(|tile_type| Some(Tile { tile_type: tile_type.clone(), content: Content::None, elevation: 0 }))
+                .collect())
+            .collect()
+    }
+
+    #[test]
+    fn path_goes_around_deep_water() {
+        let map = flat_map(&[
+            &[TileType::Grass, TileType::DeepWater, TileType::Grass],
+            &[TileType::Grass, TileType::DeepWater, TileType::Grass],
+            &[TileType::Grass, TileType::Grass, TileType::Grass],
+        ]);
+
+        let (end, path) = shortest_path(&map, (0, 0), |c| c == (0, 2)).expect("NO PATH");
+        assert_eq!(end, (0, 2));
+        assert_eq!(path.len(), 6);
+        assert_eq!(follow(&map, (0, 0), &path), Some((0, 2)));
+    }
+
+    #[test]
+    fn unknown_and_lava_tiles_block_the_path() {
+        let mut map = flat_map(&[
+            &[TileType::Grass, TileType::Lava, TileType::Grass],
+        ]);
+        map[0].push(None);
+
+        assert!(shortest_path(&map, (0, 0), |c| c == (0, 2)).is_none());
+        assert!(shortest_path(&map, (0, 2), |c| c == (0, 3)).is_none());
+    }
+}
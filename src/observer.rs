@@ -0,0 +1,96 @@
+use robotics_lib::interface::Direction;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::{Content, Tile};
+
+use crate::report::SaleReport;
+
+/// Why the seller chose a Market among the ones next to the robot
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionReason {
+    /// It is the only adjacent Market with interactions left that may be used
+    OnlyCandidate,
+    /// The sale's `MarketFilter` prefers it, see `SaleOptions::markets`
+    Preferred,
+    /// It has the most interactions left among the adjacent Markets that may be used
+    MostInteractions,
+}
+
+/// Why an item of the list passed to the seller has not been sold
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    /// Markets don't buy this item
+    NotSellable,
+    /// The backpack doesn't hold any of this item
+    NotInBackpack,
+    /// The Market ran out of interactions before getting to this item
+    NoInteractionsLeft,
+    /// The goal of the sale doesn't need this item to be sold
+    NotNeeded,
+    /// Selling this item wouldn't free any slot in the backpack, its coins take at least as many
+    /// as it frees, see `SaleGoal::FreeSlots`
+    CannotFreeSpace,
+    /// The coins of even the smallest worthwhile chunk wouldn't fit in the backpack
+    NoSpaceForCoins,
+    /// Today's quota for this item has been sold already
+    QuotaReached,
+    /// The Market took none of the units offered, the rest of this item is not offered again
+    NothingTaken,
+}
+
+/// Why a sale ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+    /// The time of day or the weather doesn't allow selling, see `SaleOptions::window`
+    OutsideWindow,
+    /// There is no Market with interactions left next to the robot
+    NoMarketNearby,
+    /// The Market next to the robot has no interactions left
+    MarketEmpty,
+    /// The Market ran out of interactions during the sale
+    OutOfInteractions,
+    /// The backpack can't hold the given amount of coins
+    NotEnoughSpace(usize),
+    /// The goal of the sale has been reached
+    GoalReached,
+    /// Today's quota of Market interactions has been used up
+    QuotaReached,
+    /// The interactions the robot holds at the Market have been used up, see `SwiftSeller::set_broker`
+    ReservationExhausted,
+    /// Every item of the list has been considered
+    ListExhausted,
+}
+
+/// Hooks into the lifecycle of a sale, register it with `SwiftSeller::add_observer`
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::observer::SaleObserver;
+/// ```
+///
+/// # Notes
+/// - every hook does nothing by default, implement only the ones you need
+pub trait SaleObserver {
+    /// The seller looked at the tile in `direction` from the robot, `None` if it can't be seen
+    fn on_neighbour(&mut self, _direction: &Direction, _tile: Option<&Tile>) {}
+
+    /// The seller chose the Market on `coordinate`, in `direction` from the robot, for `reason`
+    fn on_market_selected(&mut self, _coordinate: (usize, usize), _direction: &Direction, _interactions: usize, _reason: &SelectionReason) {}
+
+    /// The seller is about to offer `qty` units of `item` to the Market
+    fn before_put(&mut self, _item: &Content, _qty: usize) {}
+
+    /// The Market answered to the offer with `result`, the coins earned or an error
+    fn after_put(&mut self, _item: &Content, _qty: usize, _result: &Result<usize, LibError>) {}
+
+    /// The Market took `accepted` of the `offered` units of `item`, paying `coins`
+    fn on_accepted(&mut self, _item: &Content, _offered: usize, _accepted: usize, _coins: usize) {}
+
+    /// The seller skipped `item`
+    fn on_skip(&mut self, _item: &Content, _reason: &SkipReason) {}
+
+    /// The seller stopped selling
+    fn on_stop(&mut self, _reason: &StopReason) {}
+
+    /// The sale is over and succeeded
+    fn on_complete(&mut self, _report: &SaleReport) {}
+}
@@ -0,0 +1,22 @@
+use crate::chunking::Chunking;
+use crate::goal::SaleGoal;
+use crate::market_filter::MarketFilter;
+use crate::window::SaleWindow;
+
+/// How a seller sells, see `SwiftSeller::options_mut`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaleOptions {
+    /// What the sale is meant to achieve
+    pub goal: SaleGoal,
+    /// Whether to split every item in several `put`s that fit the backpack, `None` to offer it whole
+    pub chunking: Option<Chunking>,
+    /// Whether to put the items left unsold in the adjacent Crates with room when the backpack runs
+    /// short of space for the coins, see `SwiftSeller::stash`
+    pub stash_leftovers: bool,
+    /// Whether to check, after every `put`, that the backpack and the Market changed as expected
+    pub verify: bool,
+    /// The Markets the seller may use, and those it picks first, next to the robot and in `plan_route`
+    pub markets: MarketFilter,
+    /// The times of day and the weather in which the seller may sell
+    pub window: SaleWindow,
+}
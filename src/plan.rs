@@ -0,0 +1,334 @@
+use std::collections::{HashMap, VecDeque};
+use robotics_lib::interface::{go, look_at_sky, robot_map, Direction};
+use robotics_lib::runner::Runnable;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::Content;
+use robotics_lib::world::World;
+use log::debug;
+
+use crate::broker::{MarketBroker, RobotId};
+use crate::market_filter::MarketFilter;
+use crate::navigation;
+use crate::planner;
+use crate::report::SaleReport;
+use crate::route::RoutePlan;
+use crate::window::SaleWindow;
+use crate::{SwiftSeller, PUT_ENERGY_ESTIMATE};
+
+/// A single action of a sale plan
+#[derive(Debug, Clone)]
+pub enum SaleStep {
+    /// Take one step in the given direction
+    Move(Direction),
+    /// Sell at most the given quantity of each item to the adjacent Market at the given coordinate
+    Sell { market: (usize, usize), items: Vec<(Content, usize)> },
+    /// Do nothing until the robot's energy reaches the given level
+    WaitForRecharge(usize),
+}
+
+/// Where a sale plan stands after a call to `SalePlan::step`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanStatus {
+    /// The robot is out of energy, call `step` again on a later tick
+    Waiting { energy_needed: usize },
+    /// The time of day or the weather doesn't allow the next step, call `step` again on a later tick
+    Postponed,
+    /// Every step has been carried out
+    Done,
+}
+
+/// A sale that may take several ticks: walking to one or more Markets and selling there
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::plan::SalePlan;
+/// ```
+///
+/// # Notes
+/// - call `step` once per tick, it carries out as many steps as the robot's energy allows and
+///   picks up where it stopped on the next call
+#[derive(Debug, Clone, Default)]
+pub struct SalePlan {
+    steps: VecDeque<SaleStep>,
+    sold: HashMap<Content, usize>,
+    window: SaleWindow,
+    markets: MarketFilter,
+    broker: Option<(MarketBroker, RobotId)>,
+}
+
+impl SalePlan {
+    pub fn new(steps: Vec<SaleStep>) -> Self {
+        SalePlan {
+            steps: steps.into(),
+            sold: HashMap::new(),
+            window: SaleWindow::default(),
+            markets: MarketFilter::default(),
+            broker: None,
+        }
+    }
+
+    /// Walks and sells only when `window` allows it, postponing the plan otherwise
+    pub fn with_window(mut self, window: SaleWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sells only to the Markets `markets` allows, failing the sale steps at any other
+    pub fn with_markets(mut self, markets: MarketFilter) -> Self {
+        self.markets = markets;
+        self
+    }
+
+    /// Shares the Markets with other robots: the interactions of a Market are reserved before
+    /// walking to it, for `broker::TRIP_TTL`, and its sale uses no more than the robot holds there
+    pub fn with_broker(mut self, broker: MarketBroker, robot: RobotId) -> Self {
+        self.broker = Some((broker, robot));
+        self
+    }
+
+    /// Plans the cheapest trip to a known Market, followed by the sale
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    /// - `market`: The coordinate of the Market to sell to
+    /// - `items`: The items you want the tool to auto-sell
+    /// - `energy_budget`: The energy the trip may spend, regardless of how many ticks it takes
+    ///
+    /// # Errors
+    /// - `OperationNotAllowed`: The robot doesn't know of a Market with interactions left at `market`
+    /// - `CannotWalk`: The Market cannot be reached through the tiles known to the robot
+    /// - `NotEnoughEnergy`: The trip doesn't fit in `energy_budget`
+    ///
+    /// # Notes
+    /// - the quantity of every item the robot holds now is what the plan sells, items gained on the
+    ///   way are kept
+    pub fn to_market(
+        robot: &impl Runnable,
+        world: &World,
+        market: (usize, usize),
+        items: Vec<Content>,
+        energy_budget: usize
+    ) -> Result<Self, LibError> {
+        SalePlan::to_allowed_market(robot, world, market, items, energy_budget, &MarketFilter::default())
+    }
+
+    /// `to_market`, if `filter` allows the Market; the plan keeps selling only where `filter` allows
+    ///
+    /// # Errors
+    /// - `OperationNotAllowed`: `filter` doesn't allow the Market
+    /// - the errors `to_market` returns
+    pub fn to_allowed_market(
+        robot: &impl Runnable,
+        world: &World,
+        market: (usize, usize),
+        items: Vec<Content>,
+        energy_budget: usize,
+        filter: &MarketFilter
+    ) -> Result<Self, LibError> {
+
+        let map = robot_map(world).ok_or(LibError::OperationNotAllowed)?;
+        if !filter.allows(market) || navigation::market_at(&map, market) < 1 {
+            return Err(LibError::OperationNotAllowed);
+        }
+
+        let start = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        let trip = planner::plan_trip(
+            &map,
+            start,
+            |position| navigation::is_adjacent(market, position),
+            &look_at_sky(world).get_weather_condition(),
+            energy_budget,
+            0
+        )?;
+
+        let backpack = robot.get_backpack().get_contents();
+        let items = items.into_iter()
+            .map(|item| {
+                let qty = backpack.get(&item).copied().unwrap_or(0);
+                (item, qty)
+            })
+            .collect();
+        let mut steps: Vec<SaleStep> = trip.path.into_iter().map(SaleStep::Move).collect();
+        steps.push(SaleStep::Sell { market, items });
+        Ok(SalePlan::new(steps).with_markets(filter.clone()))
+    }
+
+    /// Turns a multi-stop route into a plan, selling at every stop only what the route planned there
+    /// and only where the route's filter allows
+    pub fn from_route(route: &RoutePlan) -> Self {
+        let mut steps = Vec::new();
+        for stop in &route.stops {
+            steps.extend(stop.path.iter().cloned().map(SaleStep::Move));
+            steps.push(SaleStep::Sell {
+                market: stop.market,
+                items: stop.sales.iter().map(|(item, qty)| (item.clone(), *qty)).collect(),
+            });
+        }
+        SalePlan::new(steps).with_markets(route.markets.clone())
+    }
+
+    /// The steps not carried out yet
+    pub fn remaining(&self) -> &VecDeque<SaleStep> {
+        &self.steps
+    }
+
+    /// Everything sold so far, over all ticks
+    pub fn sold(&self) -> &HashMap<Content, usize> {
+        &self.sold
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Carries out the plan until it is done or the robot runs out of energy
+    ///
+    /// # Arguments
+    /// - `robot`: The robot
+    /// - `world`: The world in which the robot is
+    ///
+    /// # Returns
+    /// - `PlanStatus`: Whether the plan is done, waits for energy or has been postponed
+    /// - `LibError`: The error that occurred
+    ///
+    /// # Errors
+    /// - `OperationNotAllowed`: A sale step is at a Market the plan's filter doesn't allow, see `with_markets`
+    /// - `OperationNotAllowed`: With a broker, no interaction could be reserved at the next Market,
+    ///   see `with_broker`; the robot doesn't move
+    /// - any error `go` or `swift_seller_at` return, other than running out of energy
+    ///
+    /// # Notes
+    /// - a step that fails stays in the plan, so that it can be retried
+    /// - a sale step that fails halfway still counts what it sold, and only the rest stays in the plan
+    /// - waiting for energy, `energy_needed` is the estimated cost of the next step, see `planner::step_cost`
+    /// - a step the window doesn't allow stays in the plan too, the robot waits where it is
+    pub fn step(
+        &mut self,
+        robot: &mut impl Runnable,
+        world: &mut World
+    ) -> Result<PlanStatus, LibError> {
+
+        while let Some(step) = self.steps.front().cloned() {
+            let energy = robot.get_energy().get_energy_level();
+            let conditions = look_at_sky(world);
+            match step {
+                SaleStep::Move(_) if !self.window.can_travel(&conditions) => {
+                    debug!("plan postponed: step=move weather={:?}", conditions.get_weather_condition());
+                    return Ok(PlanStatus::Postponed);
+                }
+                SaleStep::Sell { .. } if !self.window.can_sell(&conditions) => {
+                    debug!("plan postponed: step=sell time={:?} weather={:?}", conditions.get_time_of_day(), conditions.get_weather_condition());
+                    return Ok(PlanStatus::Postponed);
+                }
+                SaleStep::Move(_) | SaleStep::Sell { .. } if !self.hold_next_market(world) => {
+                    debug!("plan step refused, no interactions reserved");
+                    return Err(LibError::OperationNotAllowed);
+                }
+                SaleStep::Move(direction) => {
+                    match go(robot, world, direction.clone()) {
+                        Ok(_) => (),
+                        Err(LibError::NotEnoughEnergy) => {
+                            // The step costs more than the robot has, whatever the estimate says
+                            let energy_needed = SalePlan::move_cost(robot, world, &direction)
+                                .map_or(energy + 1, |cost| cost.max(energy + 1));
+                            debug!("plan waiting for energy: step=move energy={} needed={}", energy, energy_needed);
+                            return Ok(PlanStatus::Waiting { energy_needed });
+                        }
+                        Err(e) => return Err(e)
+                    }
+                }
+                SaleStep::Sell { market, items } => {
+                    // Make sure the sale doesn't run out of energy halfway
+                    let energy_needed = items.len() * PUT_ENERGY_ESTIMATE;
+                    if energy < energy_needed {
+                        debug!("plan waiting for energy: step=sell energy={} needed={}", energy, energy_needed);
+                        return Ok(PlanStatus::Waiting { energy_needed });
+                    }
+                    if !self.markets.allows(market) {
+                        debug!("plan step refused: step=sell market={:?}", market);
+                        return Err(LibError::OperationNotAllowed);
+                    }
+                    // Sell where the plan says, even if another Market is next to the robot too
+                    let reserved = self.broker.as_ref().map(|(broker, id)| broker.reserved_by(*id, market));
+                    match SwiftSeller::sell_quantities_at(robot, world, market, &items, reserved) {
+                        Ok(report) => self.record(market, &report),
+                        Err((e, report)) => {
+                            self.record(market, &report);
+                            if let Some(SaleStep::Sell { items, .. }) = self.steps.front_mut() {
+                                for (item, qty) in items.iter_mut() {
+                                    *qty -= report.items_sold.get(item).copied().unwrap_or(0).min(*qty);
+                                }
+                                items.retain(|(_, qty)| *qty > 0);
+                            }
+                            return Err(e);
+                        }
+                    }
+                }
+                SaleStep::WaitForRecharge(energy_needed) => {
+                    if energy < energy_needed {
+                        return Ok(PlanStatus::Waiting { energy_needed });
+                    }
+                }
+            }
+            self.steps.pop_front();
+        }
+        Ok(PlanStatus::Done)
+    }
+
+    fn record(&mut self, market: (usize, usize), report: &SaleReport) {
+        for (item, qty) in &report.items_sold {
+            *self.sold.entry(item.clone()).or_insert(0) += qty;
+        }
+        if let Some((broker, id)) = &self.broker {
+            broker.consume(*id, market, report.interactions_used);
+        }
+    }
+
+    /// With a broker, reserves an interaction per item at the Market of the next sale step, `false`
+    /// if none could be reserved
+    fn hold_next_market(&self, world: &World) -> bool {
+        let Some((broker, id)) = &self.broker else { return true };
+        let next = self.steps.iter().find_map(|step| match step {
+            SaleStep::Sell { market, items } => Some((*market, items.len())),
+            _ => None
+        });
+        let Some((market, items)) = next else { return true };
+        let known = || robot_map(world).map_or(0, |map| navigation::market_at(&map, market));
+        broker.reserve_for_trip(*id, market, items, known) > 0
+    }
+
+    /// The estimated energy of a step in `direction`, `None` if the robot doesn't know both tiles
+    fn move_cost(robot: &impl Runnable, world: &World, direction: &Direction) -> Option<usize> {
+        let map = robot_map(world)?;
+        let from = (robot.get_coordinate().get_row(), robot.get_coordinate().get_col());
+        let to = navigation::step(&map, from, direction)?;
+        let (Some(here), Some(there)) = (&map[from.0][from.1], &map[to.0][to.1]) else { return None };
+        Some(planner::step_cost(here, there, &look_at_sky(world).get_weather_condition()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::route::RouteStop;
+
+    use super::*;
+
+    #[test]
+    fn route_quantities_and_filter_carried_over() {
+        let mut sales = HashMap::new();
+        sales.insert(Content::Rock(0), 3);
+        let route = RoutePlan {
+            stops: vec![RouteStop { market: (0, 0), path: vec![Direction::Right], energy: 1, sales, expected_revenue: 3 }],
+            unsold: HashMap::new(),
+            markets: MarketFilter::new().with_denied((0, 1)),
+        };
+
+        let plan = SalePlan::from_route(&route);
+        assert!(matches!(
+            plan.remaining().back(),
+            Some(SaleStep::Sell { market: (0, 0), items }) if items == &vec![(Content::Rock(0), 3)]
+        ));
+        assert!(!plan.markets.allows((0, 1)));
+    }
+}
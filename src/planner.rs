@@ -0,0 +1,194 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use robotics_lib::interface::Direction;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::environmental_conditions::WeatherType;
+use robotics_lib::world::tile::Tile;
+
+use crate::navigation;
+
+/// A trip the robot can afford
+#[derive(Debug, Clone)]
+pub struct TripPlan {
+    /// The tile the trip ends on
+    pub destination: (usize, usize),
+    /// The directions to follow, one `go` each
+    pub path: Vec<Direction>,
+    /// The estimated energy the trip costs
+    pub energy: usize,
+}
+
+/// Safety margin (in percent) the planner adds to every step under `weather`
+///
+/// # Notes
+/// - these are not the library's weather costs, only tunable margins: the worse the weather, the
+///   more energy a trip keeps in reserve
+/// - only their order matters to the planner, tune them freely as long as it is kept
+pub fn weather_margin(weather: &WeatherType) -> usize {
+    match weather {
+        WeatherType::Sunny => 0,
+        WeatherType::Foggy => 10,
+        WeatherType::Rainy => 20,
+        WeatherType::TrentinoSnow => 30,
+        WeatherType::TropicalMonsoon => 50,
+    }
+}
+
+/// Estimated energy cost of walking onto `to` from `from` under `weather`, margin included, see
+/// `weather_margin`
+pub fn step_cost(from: &Tile, to: &Tile, weather: &WeatherType) -> usize {
+    let base = navigation::step_energy(from, to);
+    base + (base * weather_margin(weather)).div_ceil(100)
+}
+
+/// Plans the cheapest trip, in energy, to any tile matching `is_goal`
+///
+/// # Arguments
+/// - `map`: The robot's known map
+/// - `start`: Where the robot stands
+/// - `is_goal`: Tells whether a coordinate is a valid destination
+/// - `weather`: The current weather, which makes every step more expensive
+/// - `available_energy`: The robot's energy level
+/// - `energy_floor`: The energy that must be left once the trip is over (e.g. for the sale)
+///
+/// # Returns
+/// - `TripPlan`: The cheapest trip and its estimated cost
+/// - `LibError`: The error that occurred
+///
+/// # Errors
+/// - `CannotWalk`: No destination can be reached through known walkable tiles
+/// - `NotEnoughEnergy`: Destinations can be reached, but none without going below `energy_floor`
+///
+/// # Notes
+/// - Dijkstra over the known tiles, unknown tiles are never walked on
+pub fn plan_trip(
+    map: &[Vec<Option<Tile>>],
+    start: (usize, usize),
+    is_goal: impl Fn((usize, usize)) -> bool,
+    weather: &WeatherType,
+    available_energy: usize,
+    energy_floor: usize
+) -> Result<TripPlan, LibError> {
+
+    let budget = available_energy.saturating_sub(energy_floor);
+    let mut over_budget = false;
+
+    let mut costs: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut parents: HashMap<(usize, usize), ((usize, usize), Direction)> = HashMap::new();
+    let mut queue: BinaryHeap<Reverse<(usize, (usize, usize))>> = BinaryHeap::new();
+    costs.insert(start, 0);
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((cost, current))) = queue.pop() {
+        if costs.get(&current).is_some_and(|best| cost > *best) {
+            continue;
+        }
+
+        if is_goal(current) {
+            let mut path = Vec::new();
+            let mut position = current;
+            while position != start {
+                let (parent, direction) = parents[&position].clone();
+                path.push(direction);
+                position = parent;
+            }
+            path.reverse();
+            return Ok(TripPlan { destination: current, path, energy: cost });
+        }
+
+        let Some(here) = &map[current.0][current.1] else { continue };
+        for direction in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+            let Some(next) = navigation::step(map, current, &direction) else { continue };
+            let Some(tile) = &map[next.0][next.1] else { continue };
+            if !navigation::is_walkable(tile) {
+                continue;
+            }
+
+            let next_cost = cost + step_cost(here, tile, weather);
+            // Trips the robot can't finish are not explored any further
+            if next_cost > budget {
+                over_budget = true;
+                continue;
+            }
+            if costs.get(&next).is_none_or(|best| next_cost < *best) {
+                costs.insert(next, next_cost);
+                parents.insert(next, (current, direction));
+                queue.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    if over_budget {
+        Err(LibError::NotEnoughEnergy)
+    } else {
+        Err(LibError::CannotWalk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::tile::TileType;
+
+    use crate::navigation::tests::flat_map;
+
+    use super::*;
+
+    #[test]
+    fn detour_around_a_climb() {
+        let mut map = flat_map(&[
+            &[TileType::Grass, TileType::Grass, TileType::Grass],
+            &[TileType::Grass, TileType::Grass, TileType::Grass],
+        ]);
+        map[0][1].as_mut().unwrap().elevation = 5;
+
+        let plan = plan_trip(&map, (0, 0), |c| c == (0, 2), &WeatherType::Sunny, 1000, 0)
+            .expect("NO TRIP");
+        assert_eq!(plan.destination, (0, 2));
+        assert_eq!(plan.path.len(), 4);
+        assert_eq!(navigation::follow(&map, (0, 0), &plan.path), Some((0, 2)));
+    }
+
+    #[test]
+    fn refuse_trips_below_the_energy_floor() {
+        let mut map = flat_map(&[
+            &[TileType::Grass, TileType::Grass],
+        ]);
+        map[0][1].as_mut().unwrap().elevation = 3;
+
+        assert_eq!(
+            plan_trip(&map, (0, 0), |c| c == (0, 1), &WeatherType::Sunny, 10, 5).err(),
+            Some(LibError::NotEnoughEnergy)
+        );
+        assert_eq!(
+            plan_trip(&map, (0, 0), |c| c == (5, 5), &WeatherType::Sunny, 1000, 0).err(),
+            Some(LibError::CannotWalk)
+        );
+    }
+
+    #[test]
+    fn bad_weather_costs_more() {
+        let mut map = flat_map(&[
+            &[TileType::Grass, TileType::Hill, TileType::Hill],
+        ]);
+        // Steep enough that no margin rounds up to the same cost
+        map[0][1].as_mut().unwrap().elevation = 10;
+        map[0][2].as_mut().unwrap().elevation = 20;
+
+        // No margin in the sun, the worse the weather the larger the margin
+        let weathers = [
+            WeatherType::Sunny,
+            WeatherType::Foggy,
+            WeatherType::Rainy,
+            WeatherType::TrentinoSnow,
+            WeatherType::TropicalMonsoon,
+        ];
+        let energies: Vec<usize> = weathers.iter()
+            .map(|weather| plan_trip(&map, (0, 0), |c| c == (0, 2), weather, 1000, 0).unwrap().energy)
+            .collect();
+
+        let tiles: Vec<&Tile> = map[0].iter().map(|tile| tile.as_ref().unwrap()).collect();
+        let base: usize = tiles.windows(2).map(|step| navigation::step_energy(step[0], step[1])).sum();
+        assert_eq!(energies[0], base);
+        assert!(energies.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use robotics_lib::world::tile::Content;
+
+/// Maps any sellable item to the key the backpack uses for it, `None` if Markets don't buy it
+pub fn sellable_kind(item: &Content) -> Option<Content> {
+    match item {
+        Content::Rock(_) => Some(Content::Rock(0)),
+        Content::Tree(_) => Some(Content::Tree(0)),
+        Content::Fish(_) => Some(Content::Fish(0)),
+        _ => None
+    }
+}
+
+/// Expected coins per unit of every item a Market buys
+///
+/// # Notes
+/// - the defaults are estimates, call `record_sale` after every sale to learn the real prices
+#[derive(Debug, Clone)]
+pub struct PriceBook {
+    prices: HashMap<Content, f64>,
+}
+
+impl Default for PriceBook {
+    fn default() -> Self {
+        let mut prices = HashMap::new();
+        prices.insert(Content::Rock(0), 1.0);
+        prices.insert(Content::Tree(0), 2.0);
+        prices.insert(Content::Fish(0), 3.0);
+        PriceBook { prices }
+    }
+}
+
+impl PriceBook {
+    pub fn new() -> Self {
+        PriceBook::default()
+    }
+
+    /// Coins per unit of `item`, 0 for items Markets don't buy
+    pub fn price(&self, item: &Content) -> f64 {
+        sellable_kind(item)
+            .and_then(|kind| self.prices.get(&kind).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Overrides the price of `item`
+    pub fn set(&mut self, item: &Content, price: f64) {
+        if let Some(kind) = sellable_kind(item) {
+            self.prices.insert(kind, price);
+        }
+    }
+
+    /// Coins expected from selling `qty` units of `item`
+    pub fn expected_coins(&self, item: &Content, qty: usize) -> usize {
+        (self.price(item) * qty as f64).floor() as usize
+    }
+
+    /// Learns the price of `item` from a sale that actually happened
+    pub fn record_sale(&mut self, item: &Content, qty: usize, coins: usize) {
+        if qty > 0 {
+            self.set(item, coins as f64 / qty as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn learn_prices_from_sales() {
+        let mut prices = PriceBook::new();
+        prices.record_sale(&Content::Rock(5), 4, 10);
+
+        assert_eq!(prices.price(&Content::Rock(0)), 2.5);
+        assert_eq!(prices.expected_coins(&Content::Rock(0), 3), 7);
+        assert_eq!(prices.expected_coins(&Content::Coin(0), 3), 0);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use robotics_lib::event::events::Event;
+use robotics_lib::world::tile::Content;
+use log::debug;
+
+use crate::prices::sellable_kind;
+use crate::report::SaleReport;
+
+/// Limits on what a seller sells in a single in-game day
+///
+/// # Usage
+/// ```rust
+/// use robotics_lib::world::tile::Content;
+/// use swift_seller::quota::DailyQuota;
+/// let quota = DailyQuota::new().with_item_limit(Content::Rock(0), 20).with_interaction_limit(3);
+/// ```
+///
+/// # Notes
+/// - set it with `SwiftSeller::set_quota`, the seller keeps count of what it sells
+/// - the counters start again from zero on `DayChanged`, pass every event to `SwiftSeller::handle_event`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DailyQuota {
+    item_limits: HashMap<Content, usize>,
+    interaction_limit: Option<usize>,
+    sold_today: HashMap<Content, usize>,
+    interactions_today: usize,
+    reserved: Option<usize>,
+}
+
+impl DailyQuota {
+    pub fn new() -> Self {
+        DailyQuota::default()
+    }
+
+    /// Sells at most `qty` units of `item` per day
+    pub fn with_item_limit(mut self, item: Content, qty: usize) -> Self {
+        if let Some(kind) = sellable_kind(&item) {
+            self.item_limits.insert(kind, qty);
+        }
+        self
+    }
+
+    /// Uses at most `interactions` Market interactions per day
+    pub fn with_interaction_limit(mut self, interactions: usize) -> Self {
+        self.interaction_limit = Some(interactions);
+        self
+    }
+
+    /// The units of `item` that can still be sold today, `None` if there is no limit
+    pub fn remaining_items(&self, item: &Content) -> Option<usize> {
+        let kind = sellable_kind(item)?;
+        let limit = self.item_limits.get(&kind)?;
+        Some(limit.saturating_sub(self.sold_today.get(&kind).copied().unwrap_or(0)))
+    }
+
+    /// The Market interactions that can still be used today, `None` if there is no limit
+    pub fn remaining_interactions(&self) -> Option<usize> {
+        self.interaction_limit.map(|limit| limit.saturating_sub(self.interactions_today))
+    }
+
+    /// This quota, for a sale that may use no more than the `interactions` the robot holds at the
+    /// Market, see `broker::MarketBroker`
+    pub(crate) fn with_reservation(&self, interactions: usize) -> DailyQuota {
+        DailyQuota {
+            reserved: Some(interactions),
+            ..self.clone()
+        }
+    }
+
+    /// The interactions the sale may use at the Market, `None` if the robot doesn't share it
+    pub(crate) fn reserved(&self) -> Option<usize> {
+        self.reserved
+    }
+
+    /// The units sold today, per item
+    pub fn sold_today(&self) -> &HashMap<Content, usize> {
+        &self.sold_today
+    }
+
+    pub fn interactions_today(&self) -> usize {
+        self.interactions_today
+    }
+
+    /// Counts what a sale sold against today's limits
+    pub fn record(&mut self, report: &SaleReport) {
+        for (item, qty) in &report.items_sold {
+            if let Some(kind) = sellable_kind(item) {
+                *self.sold_today.entry(kind).or_insert(0) += qty;
+            }
+        }
+        self.interactions_today += report.interactions_used;
+    }
+
+    /// Starts a new day, the limits are kept
+    pub fn reset(&mut self) {
+        self.sold_today.clear();
+        self.interactions_today = 0;
+    }
+
+    /// Starts a new day on `DayChanged`
+    pub fn observe(&mut self, event: &Event) {
+        if let Event::DayChanged(_) = event {
+            debug!("new day, quotas reset: sold={:?} interactions={}", self.sold_today, self.interactions_today);
+            self.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::environmental_conditions::{EnvironmentalConditions, WeatherType};
+
+    use super::*;
+
+    #[test]
+    fn count_and_reset_every_day() {
+        let mut quota = DailyQuota::new()
+            .with_item_limit(Content::Rock(3), 20)
+            .with_interaction_limit(3);
+        assert_eq!(quota.remaining_items(&Content::Tree(0)), None);
+
+        let mut report = SaleReport::default();
+        report.items_sold.insert(Content::Rock(0), 15);
+        report.interactions_used = 2;
+        quota.record(&report);
+        quota.record(&report);
+        assert_eq!(quota.remaining_items(&Content::Rock(0)), Some(0));
+        assert_eq!(quota.remaining_interactions(), Some(0));
+
+        let conditions = EnvironmentalConditions::new(&[WeatherType::Sunny], 15, 12).unwrap();
+        quota.observe(&Event::DayChanged(conditions));
+        assert_eq!(quota.remaining_items(&Content::Rock(0)), Some(20));
+        assert_eq!(quota.remaining_interactions(), Some(3));
+    }
+
+    #[test]
+    fn reservation_kept_apart_from_the_limits() {
+        let mut quota = DailyQuota::new().with_interaction_limit(5);
+        quota.record(&SaleReport { interactions_used: 2, ..SaleReport::default() });
+
+        let reserved = quota.with_reservation(1);
+        assert_eq!(reserved.reserved(), Some(1));
+        assert_eq!(reserved.remaining_interactions(), Some(3));
+        assert_eq!(DailyQuota::new().with_reservation(4).remaining_interactions(), None);
+        assert_eq!(quota.reserved(), None);
+    }
+}
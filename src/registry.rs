@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use robotics_lib::event::events::Event;
+use robotics_lib::interface::robot_map;
+use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::World;
+
+/// Every Market the robot knows about and how many interactions it had when last seen
+///
+/// # Notes
+/// - Markets with 0 interactions are remembered too, so that they are not looked for again
+#[derive(Debug, Clone, Default)]
+pub struct MarketRegistry {
+    markets: HashMap<(usize, usize), usize>,
+}
+
+impl MarketRegistry {
+    pub fn new() -> Self {
+        MarketRegistry::default()
+    }
+
+    /// Remembers the Market on `coordinate` and its interactions left
+    pub fn record(&mut self, coordinate: (usize, usize), interactions: usize) {
+        self.markets.insert(coordinate, interactions);
+    }
+
+    /// Forgets about `coordinate`, e.g. because there is no Market there anymore
+    pub fn forget(&mut self, coordinate: (usize, usize)) {
+        self.markets.remove(&coordinate);
+    }
+
+    /// Records whatever `tile` on `coordinate` says about Markets
+    pub fn observe_tile(&mut self, coordinate: (usize, usize), tile: &Tile) {
+        match tile.content {
+            Content::Market(n) => self.record(coordinate, n),
+            _ => self.forget(coordinate)
+        }
+    }
+
+    /// Keeps the records up to date with the events the robot receives
+    ///
+    /// # Notes
+    /// - `TileContentUpdated` reports the new content of a tile, e.g. a Market someone sold to
+    /// - `Moved` reports the tile the robot now stands on, which can't be a Market
+    pub fn observe(&mut self, event: &Event) {
+        match event {
+            Event::TileContentUpdated(tile, coordinate) | Event::Moved(tile, coordinate) => {
+                self.observe_tile(*coordinate, tile);
+            }
+            _ => ()
+        }
+    }
+
+    /// Interactions left at the Market on `coordinate`, `None` if no Market is known there
+    pub fn interactions(&self, coordinate: (usize, usize)) -> Option<usize> {
+        self.markets.get(&coordinate).copied()
+    }
+
+    /// The known Markets with at least one interaction left, as `(coordinate, interactions)`
+    pub fn usable(&self) -> Vec<((usize, usize), usize)> {
+        let mut markets: Vec<((usize, usize), usize)> = self.markets.iter()
+            .filter(|(_, n)| **n > 0)
+            .map(|(coordinate, n)| (*coordinate, *n))
+            .collect();
+        markets.sort();
+        markets
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    /// Records every Market in the robot's known map
+    pub fn refresh(&mut self, world: &World) {
+        if let Some(map) = robot_map(world) {
+            for (i, row) in map.iter().enumerate() {
+                for (j, col) in row.iter().enumerate() {
+                    if let Some(tile) = col {
+                        if let Content::Market(n) = tile.content {
+                            self.record((i, j), n);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::tile::TileType;
+
+    use super::*;
+
+    #[test]
+    fn only_usable_markets_are_listed() {
+        let mut registry = MarketRegistry::new();
+        registry.record((0, 0), 0);
+        registry.record((3, 4), 2);
+        registry.record((1, 1), 5);
+
+        assert_eq!(registry.usable(), vec![((1, 1), 5), ((3, 4), 2)]);
+
+        // A tile without a Market overwrites what was known
+        registry.observe_tile((1, 1), &Tile { tile_type: TileType::Grass, content: Content::None, elevation: 0 });
+        assert_eq!(registry.interactions((1, 1)), None);
+        assert_eq!(registry.interactions((0, 0)), Some(0));
+    }
+
+    #[test]
+    fn follow_tile_updates() {
+        let mut registry = MarketRegistry::new();
+        registry.record((2, 2), 3);
+
+        let market = Tile { tile_type: TileType::Grass, content: Content::Market(1), elevation: 0 };
+        registry.observe(&Event::TileContentUpdated(market, (2, 2)));
+        assert_eq!(registry.interactions((2, 2)), Some(1));
+
+        let grass = Tile { tile_type: TileType::Grass, content: Content::None, elevation: 0 };
+        registry.observe(&Event::TileContentUpdated(grass.clone(), (5, 5)));
+        registry.observe(&Event::Moved(grass, (2, 2)));
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use robotics_lib::world::tile::Content;
+
+use crate::verify::InvariantViolation;
+
+/// The outcome of a sale
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaleReport {
+    /// The coordinate of the Market the items were sold to
+    pub market: Option<(usize, usize)>,
+    /// The items sold and their quantity
+    pub items_sold: HashMap<Content, usize>,
+    /// The coins earned
+    pub coins_earned: usize,
+    /// The Market interactions used, one per item sold
+    pub interactions_used: usize,
+    /// The items left unsold and put in Crates, see `SaleOptions::stash_leftovers`
+    pub stashed: HashMap<Content, usize>,
+    /// The times the Market's interactions were not what the seller expected after a `put`
+    pub surprises: Vec<MarketSurprise>,
+    /// The invariants broken by a `put`, only checked with `SaleOptions::verify`
+    pub violations: Vec<InvariantViolation>,
+}
+
+/// The Market's interactions changed behind the seller's back, e.g. because another robot used it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketSurprise {
+    /// The item just sold
+    pub item: Content,
+    /// The interactions the seller expected to be left
+    pub expected: usize,
+    /// The interactions actually left, which the sale went on with
+    pub actual: usize,
+}
+
+/// The outcome of selling and depositing the coins in a Bank, see `SwiftSeller::sell_and_bank`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BankReport {
+    /// The coordinate of the Bank the coins were deposited in, `None` if there was none next to the robot
+    pub bank: Option<(usize, usize)>,
+    /// Every sale made, in order
+    pub sales: Vec<SaleReport>,
+    /// The items sold and their quantity, over all sales
+    pub items_sold: HashMap<Content, usize>,
+    /// The coins earned, over all sales
+    pub coins_earned: usize,
+    /// The coins deposited in the Bank
+    pub coins_banked: usize,
+    /// The coins left in the backpack
+    pub coins_kept: usize,
+}
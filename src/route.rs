@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use robotics_lib::interface::Direction;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::environmental_conditions::WeatherType;
+use robotics_lib::world::tile::{Content, Tile};
+
+use crate::market_filter::MarketFilter;
+use crate::navigation;
+use crate::planner;
+use crate::prices::{sellable_kind, PriceBook};
+use crate::PUT_ENERGY_ESTIMATE;
+
+/// One Market on a route and what to sell there
+#[derive(Debug, Clone)]
+pub struct RouteStop {
+    /// The coordinate of the Market
+    pub market: (usize, usize),
+    /// The directions leading next to the Market, starting from the previous stop
+    pub path: Vec<Direction>,
+    /// The estimated energy of the trip and of the sale
+    pub energy: usize,
+    /// The items to sell and their quantity, one Market interaction each
+    pub sales: HashMap<Content, usize>,
+    /// The coins the sale is expected to earn
+    pub expected_revenue: usize,
+}
+
+/// A sequence of Markets that together buy (part of) an order
+#[derive(Debug, Clone, Default)]
+pub struct RoutePlan {
+    pub stops: Vec<RouteStop>,
+    /// What no reachable Market could buy
+    pub unsold: HashMap<Content, usize>,
+    /// The Markets the route may stop at, as the planner was given them
+    pub markets: MarketFilter,
+}
+
+impl RoutePlan {
+    pub fn expected_revenue(&self) -> usize {
+        self.stops.iter().map(|stop| stop.expected_revenue).sum()
+    }
+
+    pub fn energy(&self) -> usize {
+        self.stops.iter().map(|stop| stop.energy).sum()
+    }
+}
+
+/// What a Market with `interactions` left would buy out of `order`: the most valuable items first
+fn sales_at(order: &HashMap<Content, usize>, interactions: usize, prices: &PriceBook) -> HashMap<Content, usize> {
+    let mut items: Vec<(&Content, &usize)> = order.iter().filter(|(_, qty)| **qty > 0).collect();
+    items.sort_by_key(|(item, qty)| std::cmp::Reverse(prices.expected_coins(item, **qty)));
+    items.into_iter()
+        .take(interactions)
+        .map(|(item, qty)| (item.clone(), *qty))
+        .collect()
+}
+
+/// Plans a route through several known Markets to sell an order none of them can buy alone
+///
+/// # Arguments
+/// - `map`: The robot's known map
+/// - `start`: Where the robot stands
+/// - `markets`: The known Markets and their interactions left, see `navigation::known_markets`
+/// - `filter`: The Markets that may be stopped at, and those to stop at first
+/// - `order`: The items to sell and their quantity
+/// - `prices`: The expected price of every item
+/// - `weather`: The current weather
+/// - `energy_budget`: The energy the whole route may spend, it can span several ticks
+///
+/// # Returns
+/// - `RoutePlan`: The stops in the order they should be visited
+/// - `LibError`: The error that occurred
+///
+/// # Errors
+/// - `OperationNotAllowed`: No known Market can be reached within the budget and buy anything
+///
+/// # Notes
+/// - greedy: the next stop is always the one that earns the most coins per unit of energy
+/// - every item sold at a stop takes one Market interaction
+/// - a preferred Market that can buy something is always the next stop, before any other
+#[allow(clippy::too_many_arguments)]
+pub fn plan_route(
+    map: &[Vec<Option<Tile>>],
+    start: (usize, usize),
+    markets: &[((usize, usize), usize)],
+    filter: &MarketFilter,
+    order: &HashMap<Content, usize>,
+    prices: &PriceBook,
+    weather: &WeatherType,
+    energy_budget: usize
+) -> Result<RoutePlan, LibError> {
+
+    let mut remaining: HashMap<Content, usize> = HashMap::new();
+    for (item, qty) in order {
+        if let Some(kind) = sellable_kind(item) {
+            *remaining.entry(kind).or_insert(0) += qty;
+        }
+    }
+
+    let mut plan = RoutePlan {
+        markets: filter.clone(),
+        ..RoutePlan::default()
+    };
+    let mut position = start;
+    let mut energy_left = energy_budget;
+    let mut visited: Vec<(usize, usize)> = Vec::new();
+
+    while remaining.values().any(|qty| *qty > 0) {
+        // Evaluate every Market not visited yet from where the robot will be
+        let mut best: Option<((bool, f64), RouteStop)> = None;
+        for (market, interactions) in markets {
+            if *interactions < 1 || visited.contains(market) || !filter.allows(*market) {
+                continue;
+            }
+
+            let sales = sales_at(&remaining, *interactions, prices);
+            let sale_energy = sales.len() * PUT_ENERGY_ESTIMATE;
+            let trip = match planner::plan_trip(
+                map,
+                position,
+                |c| navigation::is_adjacent(*market, c),
+                weather,
+                energy_left,
+                sale_energy
+            ) {
+                Ok(trip) => trip,
+                Err(_) => continue
+            };
+
+            let expected_revenue: usize = sales.iter()
+                .map(|(item, qty)| prices.expected_coins(item, *qty))
+                .sum();
+            let energy = trip.energy + sale_energy;
+            let rank = (filter.prefers(*market), expected_revenue as f64 / energy.max(1) as f64);
+
+            if expected_revenue > 0 && !best.as_ref().is_some_and(|(best_rank, _)| rank <= *best_rank) {
+                best = Some((rank, RouteStop {
+                    market: *market,
+                    path: trip.path,
+                    energy,
+                    sales,
+                    expected_revenue,
+                }));
+            }
+        }
+
+        let Some((_, stop)) = best else { break };
+        for (item, qty) in &stop.sales {
+            if let Some(left) = remaining.get_mut(item) {
+                *left -= qty;
+            }
+        }
+        position = navigation::follow(map, position, &stop.path).unwrap_or(position);
+        energy_left -= stop.energy;
+        visited.push(stop.market);
+        plan.stops.push(stop);
+    }
+
+    if plan.stops.is_empty() {
+        return Err(LibError::OperationNotAllowed);
+    }
+
+    plan.unsold = remaining.into_iter().filter(|(_, qty)| *qty > 0).collect();
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::tile::TileType;
+
+    use crate::navigation::tests::flat_map;
+
+    use super::*;
+
+    #[test]
+    fn chain_two_markets() {
+        let mut map = flat_map(&[
+            &[TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass],
+            &[TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass],
+        ]);
+        map[0][0].as_mut().unwrap().content = Content::Market(1);
+        map[0][4].as_mut().unwrap().content = Content::Market(1);
+
+        let mut order = HashMap::new();
+        order.insert(Content::Rock(0), 5);
+        order.insert(Content::Fish(0), 2);
+        order.insert(Content::Tree(0), 1);
+
+        let markets = navigation::known_markets(&map);
+        let plan = plan_route(&map, (1, 2), &markets, &MarketFilter::new(), &order, &PriceBook::new(), &WeatherType::Sunny, 1000)
+            .expect("NO ROUTE");
+
+        // Two Markets with one interaction each: the two most valuable items are sold
+        assert_eq!(plan.stops.len(), 2);
+        assert!(plan.stops.iter().all(|stop| stop.sales.len() == 1 && stop.expected_revenue > 0));
+        assert_eq!(plan.unsold.get(&Content::Tree(0)), Some(&1));
+        assert_eq!(plan.expected_revenue(), 5 + 6);
+    }
+
+    #[test]
+    fn no_reachable_market() {
+        let map = flat_map(&[
+            &[TileType::Grass, TileType::DeepWater, TileType::Grass],
+        ]);
+
+        let mut order = HashMap::new();
+        order.insert(Content::Rock(0), 5);
+
+        assert_eq!(
+            plan_route(&map, (0, 0), &[((0, 2), 3)], &MarketFilter::new(), &order, &PriceBook::new(), &WeatherType::Sunny, 1000).err(),
+            Some(LibError::OperationNotAllowed)
+        );
+    }
+
+    #[test]
+    fn follow_the_filter() {
+        let mut map = flat_map(&[
+            &[TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass],
+            &[TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass, TileType::Grass],
+        ]);
+        map[0][0].as_mut().unwrap().content = Content::Market(1);
+        map[0][4].as_mut().unwrap().content = Content::Market(1);
+
+        let mut order = HashMap::new();
+        order.insert(Content::Rock(0), 5);
+        order.insert(Content::Fish(0), 2);
+
+        // The Market on the left may not be used
+        let markets = navigation::known_markets(&map);
+        let filter = MarketFilter::new().with_denied((0, 0));
+        let plan = plan_route(&map, (1, 1), &markets, &filter, &order, &PriceBook::new(), &WeatherType::Sunny, 1000)
+            .expect("NO ROUTE");
+        assert_eq!(plan.stops.len(), 1);
+        assert_eq!(plan.stops[0].market, (0, 4));
+        assert!(!plan.markets.allows((0, 0)));
+
+        // The Market on the right comes first, though the one on the left is closer
+        let filter = MarketFilter::new().with_preferred((0, 4));
+        let plan = plan_route(&map, (1, 1), &markets, &filter, &order, &PriceBook::new(), &WeatherType::Sunny, 1000)
+            .expect("NO ROUTE");
+        assert_eq!(plan.stops.iter().map(|stop| stop.market).collect::<Vec<_>>(), vec![(0, 4), (0, 0)]);
+    }
+}
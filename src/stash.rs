@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use robotics_lib::interface::put;
+use robotics_lib::runner::Runnable;
+use robotics_lib::world::tile::{Content, Tile};
+use robotics_lib::world::World;
+use log::{debug, info};
+
+use crate::adjacency;
+use crate::observer::{SaleObserver, SkipReason, StopReason};
+
+/// The items left in Crates, so that they can be collected later
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::stash::Stash;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stash {
+    stored: HashMap<(usize, usize), HashMap<Content, usize>>,
+}
+
+impl Stash {
+    pub fn new() -> Self {
+        Stash::default()
+    }
+
+    /// Records `qty` units of `item` left in the Crate at `coordinate`
+    pub fn record(&mut self, coordinate: (usize, usize), item: &Content, qty: usize) {
+        if qty > 0 {
+            *self.stored.entry(coordinate).or_default().entry(item.clone()).or_insert(0) += qty;
+        }
+    }
+
+    /// Records `qty` units of `item` collected from the Crate at `coordinate`
+    pub fn collected(&mut self, coordinate: (usize, usize), item: &Content, qty: usize) {
+        if let Some(items) = self.stored.get_mut(&coordinate) {
+            if let Some(stored) = items.get_mut(item) {
+                *stored = stored.saturating_sub(qty);
+                if *stored == 0 {
+                    items.remove(item);
+                }
+            }
+            if items.is_empty() {
+                self.stored.remove(&coordinate);
+            }
+        }
+    }
+
+    /// The items left in the Crate at `coordinate`
+    pub fn stored_at(&self, coordinate: (usize, usize)) -> Option<&HashMap<Content, usize>> {
+        self.stored.get(&coordinate)
+    }
+
+    /// Where items have been left, and what
+    pub fn crates(&self) -> &HashMap<(usize, usize), HashMap<Content, usize>> {
+        &self.stored
+    }
+
+    /// The units of `item` left over all Crates
+    pub fn total(&self, item: &Content) -> usize {
+        self.stored.values().filter_map(|items| items.get(item)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stored.is_empty()
+    }
+}
+
+/// Notices a sale running short of space for the coins, the only time leftovers are stashed
+#[derive(Debug, Default)]
+pub(crate) struct ShortOfSpace(pub(crate) bool);
+
+impl SaleObserver for ShortOfSpace {
+    fn on_skip(&mut self, _item: &Content, reason: &SkipReason) {
+        if *reason == SkipReason::NoSpaceForCoins {
+            self.0 = true;
+        }
+    }
+
+    fn on_stop(&mut self, reason: &StopReason) {
+        if let StopReason::NotEnoughSpace(_) = reason {
+            self.0 = true;
+        }
+    }
+}
+
+/// Room left in a Crate
+fn room(tile: &Tile) -> usize {
+    match &tile.content {
+        Content::Crate(range) => range.end.saturating_sub(range.start),
+        _ => 0
+    }
+}
+
+/// Put the items of `items` still in the backpack into the adjacent Crates with room
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::stash::{stash_items, Stash};
+/// ```
+///
+/// # Arguments
+/// - `robot`: The robot
+/// - `world`: The world in which the robot is
+/// - `items`: The items to put away
+/// - `stash`: Where the items put away are recorded
+///
+/// # Returns
+/// - `HashMap<Content, usize>`: The items put away and their quantity
+///
+/// # Notes
+/// - the Crate with most room is filled first
+/// - an item a Crate refuses stays in the backpack
+pub fn stash_items(
+    robot: &mut impl Runnable,
+    world: &mut World,
+    items: &[Content],
+    stash: &mut Stash
+) -> HashMap<Content, usize> {
+    let mut stashed: HashMap<Content, usize> = HashMap::new();
+
+    for item in items {
+        let held = robot.get_backpack().get_contents().get(item).copied().unwrap_or(0);
+        if held == 0 || matches!(item, Content::Coin(_)) {
+            continue;
+        }
+        let Some(target) = adjacency::best_adjacent(robot, world, |tile| room(tile) > 0, room) else {
+            debug!("no crate with room next to the robot");
+            break;
+        };
+
+        let qty = held.min(room(&target.tile));
+        match put(robot, world, item.clone(), qty, target.direction.clone()) {
+            Ok(stored) => {
+                debug!("stashed: item={:?} qty={} crate={:?}", item, stored, target.coordinate);
+                stash.record(target.coordinate, item, stored);
+                if stored > 0 {
+                    *stashed.entry(item.clone()).or_insert(0) += stored;
+                }
+            },
+            Err(e) => debug!("crate refused: item={:?} qty={} crate={:?} error={:?}", item, qty, target.coordinate, e)
+        }
+    }
+    if !stashed.is_empty() {
+        info!("leftovers stashed: items={:?}", stashed);
+    }
+    stashed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_track_of_stashed_items() {
+        let mut stash = Stash::new();
+        stash.record((2, 1), &Content::Tree(0), 3);
+        stash.record((2, 1), &Content::Rock(0), 2);
+        stash.record((5, 5), &Content::Tree(0), 1);
+        assert_eq!(stash.total(&Content::Tree(0)), 4);
+
+        stash.collected((2, 1), &Content::Tree(0), 3);
+        stash.collected((5, 5), &Content::Tree(0), 1);
+        assert_eq!(stash.total(&Content::Tree(0)), 0);
+        assert_eq!(stash.crates().len(), 1);
+        assert_eq!(stash.stored_at((2, 1)).and_then(|items| items.get(&Content::Rock(0))), Some(&2));
+    }
+}
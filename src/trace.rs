@@ -0,0 +1,135 @@
+use std::fmt;
+use robotics_lib::interface::Direction;
+use robotics_lib::utils::LibError;
+use robotics_lib::world::tile::{Content, Tile};
+
+use crate::observer::{SaleObserver, SelectionReason, SkipReason, StopReason};
+use crate::report::SaleReport;
+
+/// A single decision taken during a sale
+#[derive(Debug, Clone)]
+pub enum TraceEntry {
+    /// A tile next to the robot has been looked at, `None` if it can't be seen
+    NeighbourInspected { direction: Direction, content: Option<Content> },
+    /// One of the adjacent Markets has been chosen
+    MarketSelected { coordinate: (usize, usize), direction: Direction, interactions: usize, reason: SelectionReason },
+    /// An item has been offered to the Market
+    Offered { item: Content, qty: usize },
+    /// The Market took part or all of the offer
+    Accepted { item: Content, offered: usize, accepted: usize, coins: usize },
+    /// The Market refused the offer
+    Refused { item: Content, qty: usize, error: String },
+    /// An item of the list has not been offered
+    Skipped { item: Content, reason: SkipReason },
+    /// The sale stopped
+    Stopped(StopReason),
+    /// The sale succeeded
+    Completed { coins: usize, interactions: usize },
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEntry::NeighbourInspected { direction, content: None } =>
+                write!(f, "looked {:?}: nothing can be seen", direction),
+            TraceEntry::NeighbourInspected { direction, content: Some(content) } =>
+                write!(f, "looked {:?}: {}", direction, content),
+            TraceEntry::MarketSelected { coordinate, direction, interactions, reason } => {
+                let why = match reason {
+                    SelectionReason::OnlyCandidate => "it is the only one that may be used",
+                    SelectionReason::Preferred => "the filter prefers it",
+                    SelectionReason::MostInteractions => "it has the most interactions left",
+                };
+                write!(f, "chose the Market at {:?} ({:?}), {}: {} interactions left", coordinate, direction, why, interactions)
+            }
+            TraceEntry::Offered { item, qty } =>
+                write!(f, "offered {} x {}", qty, item),
+            TraceEntry::Accepted { item, offered, accepted, coins } =>
+                write!(f, "the Market took {} of {} x {} for {} coins", accepted, offered, item, coins),
+            TraceEntry::Refused { item, qty, error } =>
+                write!(f, "the Market refused {} x {}: {}", qty, item, error),
+            TraceEntry::Skipped { item, reason } =>
+                write!(f, "skipped {}: {:?}", item, reason),
+            TraceEntry::Stopped(reason) =>
+                write!(f, "stopped: {:?}", reason),
+            TraceEntry::Completed { coins, interactions } =>
+                write!(f, "done: {} coins earned with {} interactions", coins, interactions),
+        }
+    }
+}
+
+/// Every decision taken during a sale, in order
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::trace::SaleTrace;
+/// ```
+///
+/// # Notes
+/// - obtained through `SwiftSeller::swift_seller_traced` or `SwiftSeller::set_tracing`
+/// - printing it gives a readable, numbered explanation of the sale
+#[derive(Debug, Clone, Default)]
+pub struct SaleTrace {
+    pub entries: Vec<TraceEntry>,
+}
+
+impl SaleTrace {
+    /// Why the sale stopped, if it did
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.entries.iter().find_map(|entry| match entry {
+            TraceEntry::Stopped(reason) => Some(reason),
+            _ => None
+        })
+    }
+}
+
+impl fmt::Display for SaleTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            writeln!(f, "{:>3}. {}", i + 1, entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl SaleObserver for SaleTrace {
+    fn on_neighbour(&mut self, direction: &Direction, tile: Option<&Tile>) {
+        self.entries.push(TraceEntry::NeighbourInspected {
+            direction: direction.clone(),
+            content: tile.map(|tile| tile.content.clone()),
+        });
+    }
+
+    fn on_market_selected(&mut self, coordinate: (usize, usize), direction: &Direction, interactions: usize, reason: &SelectionReason) {
+        self.entries.push(TraceEntry::MarketSelected { coordinate, direction: direction.clone(), interactions, reason: reason.clone() });
+    }
+
+    fn before_put(&mut self, item: &Content, qty: usize) {
+        self.entries.push(TraceEntry::Offered { item: item.clone(), qty });
+    }
+
+    fn after_put(&mut self, item: &Content, qty: usize, result: &Result<usize, LibError>) {
+        if let Err(error) = result {
+            self.entries.push(TraceEntry::Refused { item: item.clone(), qty, error: format!("{:?}", error) });
+        }
+    }
+
+    fn on_accepted(&mut self, item: &Content, offered: usize, accepted: usize, coins: usize) {
+        self.entries.push(TraceEntry::Accepted { item: item.clone(), offered, accepted, coins });
+    }
+
+    fn on_skip(&mut self, item: &Content, reason: &SkipReason) {
+        self.entries.push(TraceEntry::Skipped { item: item.clone(), reason: reason.clone() });
+    }
+
+    fn on_stop(&mut self, reason: &StopReason) {
+        self.entries.push(TraceEntry::Stopped(reason.clone()));
+    }
+
+    fn on_complete(&mut self, report: &SaleReport) {
+        self.entries.push(TraceEntry::Completed {
+            coins: report.coins_earned,
+            interactions: report.interactions_used,
+        });
+    }
+}
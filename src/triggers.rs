@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use robotics_lib::event::events::Event;
+use robotics_lib::world::tile::Content;
+
+use crate::navigation;
+use crate::prices::sellable_kind;
+use crate::registry::MarketRegistry;
+
+/// A condition, described as data, under which a sale should fire
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerCondition {
+    /// The backpack gained at least the given quantity of an item since the last sale
+    GainedSinceLastSale(Content, usize),
+    /// The backpack gained at least the given quantity of sellable items, of any kind, since the last sale
+    BackpackGained(usize),
+    /// The robot moved next to a Market known to have interactions left
+    MovedNextToMarket,
+    /// The robot recharged at least the given energy since the last sale
+    EnergyRecharged(usize),
+    /// A new day started since the last sale
+    DayChanged,
+    /// Every condition holds
+    All(Vec<TriggerCondition>),
+    /// At least one condition holds
+    Any(Vec<TriggerCondition>),
+}
+
+/// Watches the events a robot receives and decides when a sale should fire
+///
+/// # Usage
+/// ```rust
+/// use swift_seller::triggers::{TriggerCondition, TriggerEngine};
+/// ```
+///
+/// # Notes
+/// - feed it every event from `handle_event` through `observe`
+/// - the sale fires when any of its conditions holds, use `All` to combine them
+/// - call `sale_done` after selling, the counters start again from zero
+#[derive(Debug, Clone, Default)]
+pub struct TriggerEngine {
+    conditions: Vec<TriggerCondition>,
+    markets: MarketRegistry,
+    gained: HashMap<Content, usize>,
+    next_to_market: bool,
+    energy_recharged: usize,
+    day_changed: bool,
+}
+
+impl TriggerEngine {
+    pub fn new(conditions: Vec<TriggerCondition>) -> Self {
+        TriggerEngine {
+            conditions,
+            ..TriggerEngine::default()
+        }
+    }
+
+    /// The Markets learnt from the events, it can be seeded with `MarketRegistry::refresh`
+    pub fn markets(&self) -> &MarketRegistry {
+        &self.markets
+    }
+
+    pub fn markets_mut(&mut self) -> &mut MarketRegistry {
+        &mut self.markets
+    }
+
+    /// Updates the engine's state with an event the robot received
+    pub fn observe(&mut self, event: &Event) {
+        match event {
+            Event::AddedToBackpack(item, qty) => {
+                if let Some(kind) = sellable_kind(item) {
+                    *self.gained.entry(kind).or_insert(0) += qty;
+                }
+            }
+            Event::TileContentUpdated(..) => {
+                self.markets.observe(event);
+            }
+            Event::Moved(_, coordinate) => {
+                self.markets.observe(event);
+                self.next_to_market = self.markets.usable().iter()
+                    .any(|(market, _)| navigation::is_adjacent(*market, *coordinate));
+            }
+            Event::EnergyRecharged(energy) => {
+                self.energy_recharged += energy;
+            }
+            Event::DayChanged(_) => {
+                self.day_changed = true;
+            }
+            _ => ()
+        }
+    }
+
+    fn holds(&self, condition: &TriggerCondition) -> bool {
+        match condition {
+            TriggerCondition::GainedSinceLastSale(item, qty) => {
+                sellable_kind(item)
+                    .and_then(|kind| self.gained.get(&kind))
+                    .is_some_and(|gained| gained >= qty)
+            }
+            TriggerCondition::BackpackGained(qty) => self.gained.values().sum::<usize>() >= *qty,
+            TriggerCondition::MovedNextToMarket => self.next_to_market,
+            TriggerCondition::EnergyRecharged(energy) => self.energy_recharged >= *energy,
+            TriggerCondition::DayChanged => self.day_changed,
+            TriggerCondition::All(conditions) => conditions.iter().all(|c| self.holds(c)),
+            TriggerCondition::Any(conditions) => conditions.iter().any(|c| self.holds(c)),
+        }
+    }
+
+    /// Whether any of the conditions holds
+    pub fn should_sell(&self) -> bool {
+        self.conditions.iter().any(|condition| self.holds(condition))
+    }
+
+    /// Starts counting again from zero, call it after every sale
+    pub fn sale_done(&mut self) {
+        self.gained.clear();
+        self.energy_recharged = 0;
+        self.day_changed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::tile::{Tile, TileType};
+
+    use super::*;
+
+    fn grass(content: Content) -> Tile {
+        Tile { tile_type: TileType::Grass, content, elevation: 0 }
+    }
+
+    #[test]
+    fn fire_after_gaining_rocks() {
+        let mut engine = TriggerEngine::new(vec![TriggerCondition::GainedSinceLastSale(Content::Rock(0), 10)]);
+
+        engine.observe(&Event::AddedToBackpack(Content::Rock(0), 6));
+        engine.observe(&Event::AddedToBackpack(Content::Tree(0), 6));
+        assert!(!engine.should_sell());
+
+        engine.observe(&Event::AddedToBackpack(Content::Rock(0), 4));
+        assert!(engine.should_sell());
+
+        engine.sale_done();
+        assert!(!engine.should_sell());
+    }
+
+    #[test]
+    fn fire_when_moving_next_to_a_known_market() {
+        let mut engine = TriggerEngine::new(vec![TriggerCondition::MovedNextToMarket]);
+
+        engine.observe(&Event::TileContentUpdated(grass(Content::Market(0)), (0, 0)));
+        engine.observe(&Event::TileContentUpdated(grass(Content::Market(2)), (4, 4)));
+
+        engine.observe(&Event::Moved(grass(Content::None), (0, 1)));
+        assert!(!engine.should_sell());
+
+        engine.observe(&Event::Moved(grass(Content::None), (3, 4)));
+        assert!(engine.should_sell());
+    }
+
+    #[test]
+    fn combine_conditions() {
+        let mut engine = TriggerEngine::new(vec![TriggerCondition::All(vec![
+            TriggerCondition::BackpackGained(5),
+            TriggerCondition::EnergyRecharged(100),
+        ])]);
+
+        engine.observe(&Event::AddedToBackpack(Content::Fish(0), 5));
+        assert!(!engine.should_sell());
+
+        engine.observe(&Event::EnergyRecharged(100));
+        assert!(engine.should_sell());
+    }
+}
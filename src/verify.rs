@@ -0,0 +1,150 @@
+use std::fmt;
+use robotics_lib::runner::Runnable;
+use robotics_lib::world::tile::Content;
+
+/// What the robot and the Market look like around a `put`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PutSnapshot {
+    /// Units of the item sold in the backpack
+    pub held: usize,
+    /// Coins in the backpack
+    pub coins: usize,
+    /// Interactions left at the Market, as its tile shows them
+    pub interactions: usize,
+}
+
+impl PutSnapshot {
+    pub(crate) fn take(robot: &impl Runnable, item: &Content, interactions: usize) -> Self {
+        let contents = robot.get_backpack().get_contents();
+        PutSnapshot {
+            held: contents.get(item).copied().unwrap_or(0),
+            coins: contents.get(&Content::Coin(0)).copied().unwrap_or(0),
+            interactions,
+        }
+    }
+}
+
+/// A quantity that must be conserved by a `put` to a Market
+#[derive(Debug, Clone, PartialEq)]
+pub enum Invariant {
+    /// The backpack holds as many fewer units of the item as the Market accepted, and the Market
+    /// accepted no more than offered
+    ItemsRemoved,
+    /// The backpack holds as many more coins as the Market paid
+    CoinsAdded,
+    /// The Market has exactly one interaction less
+    InteractionUsed,
+}
+
+/// A `put` that broke an invariant
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvariantViolation {
+    pub invariant: Invariant,
+    /// The item sold
+    pub item: Content,
+    /// The quantity offered to the Market
+    pub offered: usize,
+    /// The quantity the Market accepted
+    pub accepted: usize,
+    /// The coins `put` returned
+    pub earned: usize,
+    pub before: PutSnapshot,
+    pub after: PutSnapshot,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (what, expected, actual) = match self.invariant {
+            Invariant::ItemsRemoved => (
+                "units left the backpack",
+                self.accepted.min(self.offered) as isize,
+                self.before.held as isize - self.after.held as isize
+            ),
+            Invariant::CoinsAdded => (
+                "coins entered the backpack",
+                self.earned as isize,
+                self.after.coins as isize - self.before.coins as isize
+            ),
+            Invariant::InteractionUsed => (
+                "interactions were used",
+                1,
+                self.before.interactions as isize - self.after.interactions as isize
+            ),
+        };
+        write!(
+            f,
+            "selling {} x {} for {} coins: {} {}, expected {} (before: {:?}, after: {:?})",
+            self.offered, self.item, self.earned, actual, what, expected, self.before, self.after
+        )
+    }
+}
+
+/// Check that a successful `put` of `offered` units of `item`, of which the Market accepted
+/// `accepted` for `earned` coins, conserved everything it should
+///
+/// # Notes
+/// - the Market may accept less than offered, that is not a violation
+pub(crate) fn check_put(
+    item: &Content,
+    offered: usize,
+    accepted: usize,
+    earned: usize,
+    before: &PutSnapshot,
+    after: &PutSnapshot
+) -> Vec<InvariantViolation> {
+    let mut broken = Vec::new();
+    if accepted > offered || before.held.checked_sub(after.held) != Some(accepted) {
+        broken.push(Invariant::ItemsRemoved);
+    }
+    if after.coins.checked_sub(before.coins) != Some(earned) {
+        broken.push(Invariant::CoinsAdded);
+    }
+    if before.interactions.checked_sub(after.interactions) != Some(1) {
+        broken.push(Invariant::InteractionUsed);
+    }
+
+    broken.into_iter()
+        .map(|invariant| InvariantViolation {
+            invariant,
+            item: item.clone(),
+            offered,
+            accepted,
+            earned,
+            before: before.clone(),
+            after: after.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(held: usize, coins: usize, interactions: usize) -> PutSnapshot {
+        PutSnapshot { held, coins, interactions }
+    }
+
+    #[test]
+    fn conserved_put() {
+        let violations = check_put(&Content::Tree(0), 3, 3, 6, &snapshot(5, 1, 2), &snapshot(2, 7, 1));
+        assert!(violations.is_empty());
+
+        // The Market took only part of the offer
+        let violations = check_put(&Content::Tree(0), 3, 1, 2, &snapshot(5, 1, 2), &snapshot(4, 3, 1));
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn explain_broken_put() {
+        // The coins were counted twice and the Market didn't use an interaction
+        let violations = check_put(&Content::Rock(0), 2, 2, 4, &snapshot(2, 0, 3), &snapshot(0, 8, 3));
+        let broken: Vec<&Invariant> = violations.iter().map(|violation| &violation.invariant).collect();
+        assert_eq!(broken, vec![&Invariant::CoinsAdded, &Invariant::InteractionUsed]);
+        assert!(violations[0].to_string().contains("8 coins entered the backpack, expected 4"));
+
+        // More units left the backpack than the Market accepted
+        let violations = check_put(&Content::Rock(0), 3, 1, 1, &snapshot(3, 0, 3), &snapshot(0, 1, 2));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].to_string().contains("3 units left the backpack, expected 1"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::mem::discriminant;
+use robotics_lib::world::environmental_conditions::{DayTime, EnvironmentalConditions, WeatherType};
+
+/// When selling and walking to a Market are allowed, given the time of day and the weather
+///
+/// # Usage
+/// ```rust
+/// use robotics_lib::world::environmental_conditions::WeatherType;
+/// use swift_seller::window::SaleWindow;
+/// let window = SaleWindow::daylight().no_travel_in(WeatherType::TropicalMonsoon);
+/// ```
+///
+/// # Notes
+/// - the default window is always open
+/// - the conditions are the ones `look_at_sky` returns
+/// - two windows are equal when they list the same times of day and weather, in the same order
+#[derive(Debug, Clone, Default)]
+pub struct SaleWindow {
+    times: Option<Vec<DayTime>>,
+    no_sale: Vec<WeatherType>,
+    no_travel: Vec<WeatherType>,
+}
+
+// DayTime and WeatherType can't be compared, their variants can
+fn same_variants<T>(a: &[T], b: &[T]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| discriminant(a) == discriminant(b))
+}
+
+impl PartialEq for SaleWindow {
+    fn eq(&self, other: &Self) -> bool {
+        let times = match (&self.times, &other.times) {
+            (Some(a), Some(b)) => same_variants(a, b),
+            (a, b) => a.is_none() && b.is_none()
+        };
+        times && same_variants(&self.no_sale, &other.no_sale) && same_variants(&self.no_travel, &other.no_travel)
+    }
+}
+
+impl SaleWindow {
+    pub fn new() -> Self {
+        SaleWindow::default()
+    }
+
+    /// Sells only in the morning and in the afternoon
+    pub fn daylight() -> Self {
+        SaleWindow::new().only_during(DayTime::Morning).only_during(DayTime::Afternoon)
+    }
+
+    /// Sells during `time`, and the other times given this way, only
+    pub fn only_during(mut self, time: DayTime) -> Self {
+        self.times.get_or_insert_with(Vec::new).push(time);
+        self
+    }
+
+    /// Doesn't sell under `weather`
+    pub fn no_sale_in(mut self, weather: WeatherType) -> Self {
+        self.no_sale.push(weather);
+        self
+    }
+
+    /// Doesn't walk to a Market under `weather`
+    pub fn no_travel_in(mut self, weather: WeatherType) -> Self {
+        self.no_travel.push(weather);
+        self
+    }
+
+    /// Whether a sale can be made under `conditions`
+    pub fn can_sell(&self, conditions: &EnvironmentalConditions) -> bool {
+        let time = discriminant(&conditions.get_time_of_day());
+        let weather = discriminant(&conditions.get_weather_condition());
+        !self.times.as_ref().is_some_and(|times| times.iter().all(|t| discriminant(t) != time))
+            && self.no_sale.iter().all(|w| discriminant(w) != weather)
+    }
+
+    /// Whether the robot can walk to a Market under `conditions`
+    pub fn can_travel(&self, conditions: &EnvironmentalConditions) -> bool {
+        let weather = discriminant(&conditions.get_weather_condition());
+        self.no_travel.iter().all(|w| discriminant(w) != weather)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(weather: WeatherType, hour: u8) -> EnvironmentalConditions {
+        EnvironmentalConditions::new(&[weather], 15, hour).unwrap()
+    }
+
+    #[test]
+    fn sell_in_daylight_only() {
+        let window = SaleWindow::daylight();
+        assert!(window.can_sell(&conditions(WeatherType::Sunny, 10)));
+        assert!(!window.can_sell(&conditions(WeatherType::Sunny, 2)));
+        assert!(window.can_travel(&conditions(WeatherType::Sunny, 2)));
+    }
+
+    #[test]
+    fn stay_in_during_storms() {
+        let window = SaleWindow::new()
+            .no_sale_in(WeatherType::TrentinoSnow)
+            .no_travel_in(WeatherType::TropicalMonsoon);
+        assert!(window.can_sell(&conditions(WeatherType::TropicalMonsoon, 10)));
+        assert!(!window.can_travel(&conditions(WeatherType::TropicalMonsoon, 10)));
+        assert!(!window.can_sell(&conditions(WeatherType::TrentinoSnow, 10)));
+        assert!(window.can_travel(&conditions(WeatherType::Rainy, 10)));
+    }
+
+    #[test]
+    fn compare_by_variant() {
+        assert_eq!(SaleWindow::daylight(), SaleWindow::new().only_during(DayTime::Morning).only_during(DayTime::Afternoon));
+        assert_ne!(SaleWindow::daylight(), SaleWindow::new());
+        assert_ne!(SaleWindow::new().no_sale_in(WeatherType::Rainy), SaleWindow::new().no_travel_in(WeatherType::Rainy));
+        assert_ne!(SaleWindow::new().only_during(DayTime::Night), SaleWindow::new().only_during(DayTime::Morning));
+    }
+}